@@ -0,0 +1,266 @@
+use std::collections::HashMap;
+use std::vec::Vec;
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use countvectorizer;
+use linalg;
+use ndarray_extension;
+use sparse::CsrMatrix;
+use tokenizer::Tokenizer;
+
+/// Builds distributional word vectors from a collection of documents (i.e.
+/// Vec of string slices), following Levy, Goldberg and Dagan (2015). Words
+/// are the unigram tokens of a Tokenizer, and pairs of words at most window
+/// tokens apart within a document co-occur, weighted by their distance (see
+/// weighting). Co-occurrences are turned into positive pointwise mutual
+/// information (PPMI),
+///
+/// PPMI(w, c) = max(0, ln(P(w, c) / (P(w) P_α(c))))
+///
+/// where P_α(c) ∝ count(c)^α smooths the context distribution, which
+/// lowers the PMI of pairs with rare contexts. If n_components is set, the
+/// PPMI matrix is reduced by a truncated SVD (see linalg::randomized_svd())
+/// to dense vectors U·S^½; otherwise the word vectors are the rows of the
+/// PPMI matrix.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::cooccurrence::CooccurrenceVectorizer;
+///
+/// let docs = vec![
+///     "the cat drinks milk",
+///     "the dog drinks water",
+///     "the cat chases the dog",
+///     "stocks fell on the market",
+///     "the market rallied and stocks rose",
+/// ];
+///
+/// let mut vectorizer = CooccurrenceVectorizer::new(2, "lower");
+/// vectorizer.n_components = Some(3);
+/// let vectors = vectorizer.fit_transform(docs);
+///
+/// assert_eq!((vectorizer.vocabulary_.len(), 3), vectors.dim());
+/// println!("Similar to cat: {:?}", vectorizer.most_similar("cat", 2));
+/// ```
+///
+pub struct CooccurrenceVectorizer {
+    /// Maximum distance (in tokens) between co-occurring words.
+    pub window: usize,
+
+    /// The case of the resulting tokens. Options are "upper" and "lower".
+    /// Other inputs will use default. See also tokenizer::Tokenizer for
+    /// details.
+    pub case: String,
+
+    /// Weight of a co-occurrence at distance d. Options are "harmonic"
+    /// (1 / d, as GloVe), "linear" ((window - d + 1) / window, as the
+    /// dynamic window of word2vec) and "none" (1). Default is "harmonic".
+    /// Panics on any other value.
+    pub weighting: String,
+
+    /// Words occurring fewer times are left out of the vocabulary (and of
+    /// the windows). Default is 1.
+    pub min_count: u64,
+
+    /// Exponent α of the context distribution smoothing. 1.0 gives the
+    /// plain PPMI. Default is 0.75.
+    pub context_smoothing: f64,
+
+    /// If Some, the number of dimensions of the SVD-reduced word vectors;
+    /// if None, word vectors are the rows of the PPMI matrix. Default is
+    /// None.
+    pub n_components: Option<usize>,
+
+    /// Seed of the randomized SVD. Default is 0.
+    pub seed: u64,
+
+    /// HashMap containing the vocabulary (token as String) as keys and their
+    /// IDs (u64, rows and columns of the matrices) as values, in order of
+    /// first occurrence.
+    pub vocabulary_: HashMap<String, u64>,
+
+    /// Number of occurrences of each word.
+    pub word_counts_: Array1<u64>,
+
+    /// Distance weighted co-occurrence counts (words × words, symmetric).
+    pub cooccurrence_: CsrMatrix<f64>,
+
+    /// PPMI of each pair of words (words × contexts).
+    pub ppmi_: CsrMatrix<f64>,
+
+    /// Vector of each word (rows).
+    pub vectors_: Array2<f64>,
+}
+
+impl CooccurrenceVectorizer {
+
+    /// Create a new instance of CooccurrenceVectorizer with
+    /// weighting="harmonic", min_count=1, context_smoothing=0.75,
+    /// n_components=None and seed=0. Options can be changed via the public
+    /// fields before fitting.
+    ///
+    pub fn new(window: usize, case: &str) -> CooccurrenceVectorizer {
+        CooccurrenceVectorizer {
+            window,
+            case: case.to_string(),
+            weighting: "harmonic".to_string(),
+            min_count: 1,
+            context_smoothing: 0.75,
+            n_components: None,
+            seed: 0,
+            vocabulary_: HashMap::new(),
+            word_counts_: Array1::<u64>::zeros(0),
+            cooccurrence_: CsrMatrix::from_rows(Vec::new(), 0),
+            ppmi_: CsrMatrix::from_rows(Vec::new(), 0),
+            vectors_: Array2::<f64>::zeros((0, 0)),
+        }
+    }
+
+    fn _distance_weight(&self, distance: usize) -> f64 {
+        match self.weighting.as_str() {
+            "harmonic" => 1. / distance as f64,
+            "linear" => (self.window - distance + 1) as f64 / self.window as f64,
+            "none" => 1.,
+            _ => panic!("unknown weighting: {} (options are \"harmonic\", \"linear\" and \"none\")", self.weighting),
+        }
+    }
+
+    // Words of each document as vocabulary IDs, after building the
+    // vocabulary of the words occurring at least min_count times
+    fn _build_vocabulary(&mut self, tokenized_docs: &[Vec<String>]) -> Vec<Vec<usize>> {
+        let mut counts: HashMap<&str, u64> = HashMap::new();
+        let mut words: Vec<&str> = Vec::new();
+        for token in tokenized_docs.iter().flat_map(|doc| doc.iter()) {
+            let count = counts.entry(token.as_str()).or_insert(0);
+            if *count == 0 {
+                words.push(token.as_str());
+            }
+            *count += 1;
+        }
+
+        let kept: Vec<&str> = words.into_iter()
+            .filter(|word| counts[word] >= self.min_count)
+            .collect();
+        self.word_counts_ = kept.iter().map(|word| counts[word]).collect();
+        self.vocabulary_ = kept.iter()
+            .enumerate()
+            .map(|(index, word)| (word.to_string(), index as u64))
+            .collect();
+
+        tokenized_docs.iter()
+            .map(|doc| doc.iter()
+                .filter_map(|token| self.vocabulary_.get(token.as_str()).map(|&id| id as usize))
+                .collect())
+            .collect()
+    }
+
+    fn _count_cooccurrences(&self, docs: &[Vec<usize>]) -> CsrMatrix<f64> {
+        let num_words = self.vocabulary_.len();
+        let mut rows: Vec<HashMap<usize, f64>> = vec![HashMap::new(); num_words];
+        for doc in docs {
+            for (position, &word) in doc.iter().enumerate() {
+                let end = (position + self.window + 1).min(doc.len());
+                for (distance, &context) in doc[position + 1..end].iter().enumerate() {
+                    let weight = self._distance_weight(distance + 1);
+                    *rows[word].entry(context).or_insert(0.) += weight;
+                    *rows[context].entry(word).or_insert(0.) += weight;
+                }
+            }
+        }
+        let rows = rows.into_iter().map(|row| row.into_iter().collect()).collect();
+        CsrMatrix::from_rows(rows, num_words)
+    }
+
+    fn _ppmi(&self, cooccurrence: &CsrMatrix<f64>) -> CsrMatrix<f64> {
+        let (num_words, _) = cooccurrence.dim();
+        let mut word_sums = Array1::<f64>::zeros(num_words);
+        let mut context_sums = Array1::<f64>::zeros(num_words);
+        for index_row in 0..num_words {
+            let (indices, data) = cooccurrence.row(index_row);
+            for (&index_col, &value) in indices.iter().zip(data.iter()) {
+                word_sums[index_row] += value;
+                context_sums[index_col] += value;
+            }
+        }
+        let total = word_sums.sum();
+        let smoothed_contexts = context_sums.mapv(|e| e.powf(self.context_smoothing));
+        let smoothed_total = smoothed_contexts.sum();
+
+        let rows = (0..num_words)
+            .map(|index_row| {
+                let (indices, data) = cooccurrence.row(index_row);
+                indices.iter()
+                    .zip(data.iter())
+                    .map(|(&index_col, &value)| {
+                        let p_word_context = value / total;
+                        let p_word = word_sums[index_row] / total;
+                        let p_context = smoothed_contexts[index_col] / smoothed_total;
+                        (index_col, (p_word_context / (p_word * p_context)).ln().max(0.))
+                    })
+                    .collect()
+            })
+            .collect();
+        CsrMatrix::from_rows(rows, num_words)
+    }
+
+    /// Count co-occurrences over the collection of documents, and compute
+    /// the PPMI matrix and the word vectors. Any previously learned state is
+    /// discarded. Panics if n_components exceeds the vocabulary size.
+    ///
+    pub fn fit(&mut self, docs: Vec<&str>) {
+        let tokenizer = Tokenizer::new((1, 1), self.case.as_str());
+        let tokenized_docs = tokenizer.tokenize(docs);
+        let docs = self._build_vocabulary(&tokenized_docs);
+
+        self.cooccurrence_ = self._count_cooccurrences(&docs);
+        self.ppmi_ = self._ppmi(&self.cooccurrence_);
+        self.vectors_ = match self.n_components {
+            Some(n_components) => {
+                let (u, s, _) = linalg::randomized_svd(&self.ppmi_, n_components, 10, 5, self.seed);
+                u * &s.mapv(f64::sqrt)
+            },
+            None => self.ppmi_.to_dense(),
+        };
+    }
+
+    /// Fit to the collection of documents, then return the word vectors (a
+    /// row per word of vocabulary_). See fit().
+    ///
+    pub fn fit_transform(&mut self, docs: Vec<&str>) -> Array2<f64> {
+        self.fit(docs);
+        self.vectors_.clone()
+    }
+
+    /// Tokens of the vocabulary ordered by ID, i.e. the word of each row of
+    /// vectors_.
+    ///
+    pub fn get_feature_names(&self) -> Vec<String> {
+        countvectorizer::feature_names(&self.vocabulary_)
+    }
+
+    /// Vector of a word, or None if it is not in the vocabulary.
+    ///
+    pub fn get_vector(&self, word: &str) -> Option<ArrayView1<'_, f64>> {
+        self.vocabulary_.get(word).map(|&id| self.vectors_.row(id as usize))
+    }
+
+    /// The k words with the most similar vectors (cosine similarity) to a
+    /// word, as (word, similarity) pairs in descending order of similarity.
+    /// The word itself is excluded. Returns an empty Vec if the word is not
+    /// in the vocabulary.
+    ///
+    pub fn most_similar(&self, word: &str, k: usize) -> Vec<(String, f64)> {
+        let id = match self.vocabulary_.get(word) {
+            Some(&id) => id as usize,
+            None => return Vec::new(),
+        };
+        let query = self.vectors_.select(Axis(0), &[id]);
+        let feature_names = self.get_feature_names();
+        ndarray_extension::nearest_neighbors(&query, &self.vectors_, k + 1, "cosine", 1)[0].iter()
+            .filter(|&&(index_row, _)| index_row != id)
+            .take(k)
+            .map(|&(index_row, similarity)| (feature_names[index_row].clone(), similarity))
+            .collect()
+    }
+}
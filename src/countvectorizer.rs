@@ -1,7 +1,13 @@
 use std::vec::Vec;
 use std::collections::HashMap;
+#[cfg(feature = "parallel")]
+use std::cmp;
 use ndarray::Array2;
+use num::{NumCast, Zero};
+use ndarray_extension;
 use tokenizer::Tokenizer;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 
 /// Struct that convers a collection of documents (i.e. Vec<&str>) into a
@@ -35,31 +41,34 @@ impl CountVectorizer {
         // Return a new instance
         CountVectorizer {
             vocabulary_: map,
-            ngram_range: ngram_range,
+            ngram_range,
             case: case.to_string(),
         }
     }
 
-    // Function to conver Vec<HashMap<u64, u64>> into Array2<u64>, where each
+    // Function to conver Vec<HashMap<u64, u64>> into Array2<T>, where each
     // column corresponds to a key in the HashMap and the value of the HashMap
     // the count for that key String for a row in the resulting matrix, which 
-    // represents a document.
+    // represents a document. Returns None if a count does not fit in T.
     //
-    fn _sort_vocabulary_count(&self, vec_of_map: Vec<HashMap<u64, u64>>) -> Array2<u64>{
+    fn _sort_vocabulary_count<T: NumCast + Zero + Clone>(&self, vec_of_map: Vec<HashMap<u64, u64>>) -> Option<Array2<T>>{
         let num_rows = vec_of_map.len();
         let num_columns = self.vocabulary_.len();
-        let mut sorted_vec = Array2::<u64>::zeros((num_rows, num_columns));
+        let mut sorted_vec = Array2::<T>::zeros((num_rows, num_columns));
 
         for i in 0..num_rows {
-            for key in vec_of_map[i].keys() {
-                sorted_vec[[i, (*key as usize)]] = *vec_of_map[i].get(key).unwrap();
+            for (key, count) in vec_of_map[i].iter() {
+                sorted_vec[[i, (*key as usize)]] = T::from(*count)?;
             }
         }
-        sorted_vec
+        Some(sorted_vec)
     }
 
     /// Transform the collection of documents into word frequency count
     /// matrix. 'fit' part refers to establishment of the vocabulary HashMap.
+    /// With the "parallel" feature, documents are tokenized and counted in
+    /// parallel, and the resulting vocabulary and matrix are identical to
+    /// the sequential ones.
     /// 
     /// # Examples
     /// The text below is an excerpt from the Foreword section of “The Rust 
@@ -124,26 +133,127 @@ impl CountVectorizer {
     /// println!("Count Vector :\n{:?}", x);
     /// ```
     pub fn fit_transform(&mut self, docs: Vec<&str>) -> Array2<u64> {
+        // u64 counts cannot overflow
+        self.fit_transform_as::<u64>(docs).unwrap()
+    }
+
+    /// Same as fit_transform(), but the counts are stored with the element
+    /// type T (e.g. u8, u16, u32 or f32) instead of u64, without creating a
+    /// u64 matrix first. Returns None if a count does not fit in T; the
+    /// vocabulary is fitted regardless.
+    /// 
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    /// 
+    /// use vectorizer::countvectorizer::CountVectorizer;
+    /// 
+    /// let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    /// let x = vectorizer.fit_transform_as::<u16>(vec!["apple apple banana"]).unwrap();
+    /// assert_eq!(2u16, x[[0, 0]]);
+    /// 
+    /// // 300 occurrences do not fit in u8
+    /// let doc = vec!["apple"; 300].join(" ");
+    /// assert!(vectorizer.fit_transform_as::<u8>(vec![doc.as_str()]).is_none());
+    /// ```
+    pub fn fit_transform_as<T: NumCast + Zero + Clone>(&mut self, docs: Vec<&str>) -> Option<Array2<T>> {
         // tokenize the document collection
         let tk = Tokenizer::new(self.ngram_range, self.case.as_str());
         let _tokenized_docs = tk.tokenize(docs);
 
+        // Start from an empty vocabulary and count
+        self.vocabulary_.clear();
+        let vec_of_map = self._extend_vocabulary_and_count(_tokenized_docs);
+        self._sort_vocabulary_count(vec_of_map)   // Return the count matrix
+    }
+
+    /// Establish the vocabulary HashMap from the collection of documents.
+    /// Any previously learned vocabulary is discarded. See also
+    /// partial_fit().
+    /// 
+    pub fn fit(&mut self, docs: Vec<&str>) {
+        self.vocabulary_.clear();
+        self.partial_fit(docs);
+    }
+
+    /// Extend the vocabulary HashMap with the tokens of a batch of documents.
+    /// Existing token IDs are kept and new tokens get the next IDs in order
+    /// of appearance, so that calling partial_fit() on consecutive batches
+    /// gives the same vocabulary as fit() on the concatenated batches.
+    /// 
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    /// 
+    /// use vectorizer::countvectorizer::CountVectorizer;
+    /// 
+    /// let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    /// vectorizer.partial_fit(vec!["apple banana", "banana"]);
+    /// vectorizer.partial_fit(vec!["orange apple"]);
+    /// 
+    /// assert_eq!(0, vectorizer.vocabulary_["apple"]);
+    /// assert_eq!(1, vectorizer.vocabulary_["banana"]);
+    /// assert_eq!(2, vectorizer.vocabulary_["orange"]);
+    /// ```
+    pub fn partial_fit(&mut self, docs: Vec<&str>) {
+        let tk = Tokenizer::new(self.ngram_range, self.case.as_str());
+        let _tokenized_docs = tk.tokenize(docs);
+        self._extend_vocabulary_and_count(_tokenized_docs);
+    }
+
+    // Same as partial_fit(), but also return the count matrix of the batch
+    // over the extended vocabulary, so that the batch is tokenized and
+    // counted only once (see TfidfVectorizer::partial_fit()).
+    //
+    pub(crate) fn partial_fit_transform(&mut self, docs: Vec<&str>) -> Array2<u64> {
+        let tk = Tokenizer::new(self.ngram_range, self.case.as_str());
+        let _tokenized_docs = tk.tokenize(docs);
+        let vec_of_map = self._extend_vocabulary_and_count(_tokenized_docs);
+        // u64 counts cannot overflow
+        self._sort_vocabulary_count(vec_of_map).unwrap()
+    }
+
+    /// Transform the collection of documents into word frequency count
+    /// matrix using the vocabulary learned by fit() or partial_fit(). Tokens
+    /// not in the vocabulary are ignored.
+    /// 
+    pub fn transform(&self, docs: Vec<&str>) -> Array2<u64> {
+        // u64 counts cannot overflow
+        self.transform_as::<u64>(docs).unwrap()
+    }
+
+    /// Same as transform(), but the counts are stored with the element type
+    /// T. Returns None if a count does not fit in T. See also
+    /// fit_transform_as().
+    /// 
+    pub fn transform_as<T: NumCast + Zero + Clone>(&self, docs: Vec<&str>) -> Option<Array2<T>> {
+        let tk = Tokenizer::new(self.ngram_range, self.case.as_str());
+        let _tokenized_docs = tk.tokenize(docs);
+        count_tokens(&self.vocabulary_, _tokenized_docs)
+    }
+
+    // Count tokens of each tokenized document, adding new tokens to
+    // vocabulary_ with IDs following the existing ones. Returns the
+    // Vec<HashMap<u64, u64>> of token ID counts per document.
+    //
+    #[cfg(not(feature = "parallel"))]
+    fn _extend_vocabulary_and_count(&mut self, tokenized_docs: Vec<Vec<String>>) -> Vec<HashMap<u64, u64>> {
         // Vec to store vocab. count HashMap. Variable to return.
         let mut vec_of_map: Vec<HashMap<u64, u64>> = Vec::new();
 
         // Collect vocabulary
-        let mut vocab_indexer: u64 = 0;             // indexer for unique words
+        let mut vocab_indexer = self.vocabulary_.len() as u64;  // indexer for unique words
 
-        for _doc in _tokenized_docs {
+        for _doc in tokenized_docs {
             // HashMap to store vocab. counts for a doc
             let mut _vocab_counts: HashMap<u64, u64> = HashMap::new();
 
             for _token in _doc {
                 // if _token is a new word, add to vocabulary_ and vocabulary_counts_
                 if !self.vocabulary_.contains_key(_token.as_str()) {
-                    self.vocabulary_.insert(_token, vocab_indexer.clone());
-                    _vocab_counts.insert(vocab_indexer.clone(), 1);
-                    vocab_indexer = vocab_indexer + 1;
+                    self.vocabulary_.insert(_token, vocab_indexer);
+                    _vocab_counts.insert(vocab_indexer, 1);
+                    vocab_indexer += 1;
                 } else {        // Otherwise add vocab counts
                     let vocab_ind = self.vocabulary_[_token.as_str()];
                     *(_vocab_counts).entry(vocab_ind).or_insert(0) += 1;
@@ -151,8 +261,122 @@ impl CountVectorizer {
             }
             vec_of_map.push(_vocab_counts);
         }
-        let sorted_vec = self._sort_vocabulary_count(vec_of_map);
-        sorted_vec   // Return the Vec of count HashMaps
+        vec_of_map
+    }
+
+    // Parallel version of _extend_vocabulary_and_count(). Documents are
+    // split into contiguous chunks, and each chunk is counted with its own
+    // local vocabulary (IDs in order of appearance within the chunk). The
+    // local vocabularies are then merged into vocabulary_ chunk by chunk in
+    // local ID order, which assigns the same IDs as the sequential version.
+    //
+    #[cfg(feature = "parallel")]
+    fn _extend_vocabulary_and_count(&mut self, tokenized_docs: Vec<Vec<String>>) -> Vec<HashMap<u64, u64>> {
+        let num_chunks = 4 * rayon::current_num_threads();
+        let chunk_size = cmp::max(1, tokenized_docs.len().div_ceil(num_chunks));
+
+        // Count each chunk with a local vocabulary
+        let local_results: Vec<_> = tokenized_docs
+            .par_chunks(chunk_size)
+            .map(|chunk| {
+                let mut local_vocabulary: HashMap<&str, u64> = HashMap::new();
+                let mut local_tokens: Vec<&str> = Vec::new();
+                let mut local_counts: Vec<HashMap<u64, u64>> = Vec::with_capacity(chunk.len());
+                for _doc in chunk {
+                    let mut _vocab_counts: HashMap<u64, u64> = HashMap::new();
+                    for _token in _doc {
+                        let next_index = local_tokens.len() as u64;
+                        let local_index = *local_vocabulary.entry(_token.as_str())
+                            .or_insert_with(|| {
+                                local_tokens.push(_token.as_str());
+                                next_index
+                            });
+                        *_vocab_counts.entry(local_index).or_insert(0) += 1;
+                    }
+                    local_counts.push(_vocab_counts);
+                }
+                (local_tokens, local_counts)
+            })
+            .collect();
+
+        // Merge local vocabularies in chunk order and remap the counts
+        let mut vec_of_map: Vec<HashMap<u64, u64>> = Vec::with_capacity(tokenized_docs.len());
+        for (local_tokens, local_counts) in local_results {
+            let mut local_to_global: Vec<u64> = Vec::with_capacity(local_tokens.len());
+            for _token in local_tokens {
+                let global_index = match self.vocabulary_.get(_token) {
+                    Some(index) => *index,
+                    None => {
+                        let next_index = self.vocabulary_.len() as u64;
+                        self.vocabulary_.insert(_token.to_string(), next_index);
+                        next_index
+                    },
+                };
+                local_to_global.push(global_index);
+            }
+            for _vocab_counts in local_counts {
+                vec_of_map.push(_vocab_counts.into_iter()
+                    .map(|(local_index, count)| (local_to_global[local_index as usize], count))
+                    .collect());
+            }
+        }
+        vec_of_map
+    }
+
+    /// Tokens of the vocabulary ordered by token ID, i.e. the token of each
+    /// column of the count matrix.
+    /// 
+    pub fn get_feature_names(&self) -> Vec<String> {
+        feature_names(&self.vocabulary_)
+    }
+
+    /// Keep only the vocabulary columns in support (e.g. the columns chosen
+    /// by featureselection::SelectKBest), so that transform() yields only
+    /// those columns. Token IDs are renumbered following the order of the
+    /// remaining columns. Panics if a column is not in the vocabulary.
+    /// 
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    /// 
+    /// use vectorizer::countvectorizer::CountVectorizer;
+    /// 
+    /// let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    /// vectorizer.fit(vec!["apple banana orange"]);
+    /// vectorizer.restrict_vocabulary(&[0, 2]);
+    /// 
+    /// assert_eq!(vec!["apple", "orange"], vectorizer.get_feature_names());
+    /// ```
+    pub fn restrict_vocabulary(&mut self, support: &[usize]) {
+        self.vocabulary_ = restrict_vocabulary(&self.vocabulary_, support);
+    }
+
+    /// Map each row of a count matrix back to the tokens with non-zero
+    /// counts, in order of token ID.
+    /// 
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    /// 
+    /// use vectorizer::countvectorizer::CountVectorizer;
+    /// 
+    /// let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    /// let x = vectorizer.fit_transform(vec!["apple banana apple", "orange"]);
+    /// 
+    /// let tokens = vectorizer.inverse_transform(&x);
+    /// assert_eq!(vec!["apple", "banana"], tokens[0]);
+    /// assert_eq!(vec!["orange"], tokens[1]);
+    /// ```
+    pub fn inverse_transform<T: Clone + Zero>(&self, matrix: &Array2<T>) -> Vec<Vec<String>> {
+        inverse_transform(&self.get_feature_names(), matrix)
+    }
+
+    /// Same as inverse_transform(), but each token comes with its count and
+    /// tokens are sorted by count in descending order (ties in order of
+    /// token ID).
+    /// 
+    pub fn inverse_transform_with_weights<T: Clone + Zero + PartialOrd>(&self, matrix: &Array2<T>) -> Vec<Vec<(String, T)>> {
+        inverse_transform_with_weights(&self.get_feature_names(), matrix)
     }
 
     /// Utility function to create a reverse vocabulary map, where the token
@@ -168,3 +392,88 @@ impl CountVectorizer {
     }
 
 }
+
+// Count the tokens of tokenized documents into a matrix with a column per
+// vocabulary entry (the column index being the token ID). Tokens that are
+// not in the vocabulary are ignored. Returns None if a count does not fit
+// in T.
+//
+pub(crate) fn count_tokens<T: NumCast + Zero + Clone>(vocabulary: &HashMap<String, u64>, tokenized_docs: Vec<Vec<String>>) -> Option<Array2<T>> {
+    let num_rows = tokenized_docs.len();
+    let num_columns = vocabulary.len();
+    let mut counts = Array2::<T>::zeros((num_rows, num_columns));
+
+    for (index_row, doc) in tokenized_docs.iter().enumerate() {
+        let mut doc_counts: HashMap<u64, u64> = HashMap::new();
+        for token in doc {
+            if let Some(index_col) = vocabulary.get(token.as_str()) {
+                *doc_counts.entry(*index_col).or_insert(0) += 1;
+            }
+        }
+        for (index_col, count) in doc_counts {
+            counts[[index_row, index_col as usize]] = T::from(count)?;
+        }
+    }
+    Some(counts)
+}
+
+// Tokens of a vocabulary HashMap ordered by token ID
+//
+pub(crate) fn feature_names(vocabulary: &HashMap<String, u64>) -> Vec<String> {
+    let mut tokens = vec![String::new(); vocabulary.len()];
+    for (token, index) in vocabulary.iter() {
+        tokens[*index as usize] = token.clone();
+    }
+    tokens
+}
+
+// Vocabulary with only the token IDs in support, renumbered in order of
+// token ID. Panics if a token ID is not in the vocabulary.
+//
+pub(crate) fn restrict_vocabulary(vocabulary: &HashMap<String, u64>, support: &[usize]) -> HashMap<String, u64> {
+    let mut support = support.to_vec();
+    support.sort_unstable();
+    support.dedup();
+    if let Some(&index_col) = support.last() {
+        assert!(index_col < vocabulary.len(),
+            "column {} is out of the vocabulary of {} tokens", index_col, vocabulary.len());
+    }
+    let names = feature_names(vocabulary);
+    support.iter()
+        .enumerate()
+        .map(|(new_index, &index_col)| (names[index_col].clone(), new_index as u64))
+        .collect()
+}
+
+// Tokens with non-zero values for each row of a matrix, in column order
+//
+pub(crate) fn inverse_transform<T: Clone + Zero>(feature_names: &[String], matrix: &Array2<T>) -> Vec<Vec<String>> {
+    assert_eq!(feature_names.len(), matrix.ncols(), "number of columns differs from vocabulary size");
+    matrix.outer_iter()
+        .map(|row| {
+            row.iter()
+                .enumerate()
+                .filter(|&(_, e)| !e.is_zero())
+                .map(|(index_col, _)| feature_names[index_col].clone())
+                .collect()
+        })
+        .collect()
+}
+
+// (token, value) pairs with non-zero values for each row of a matrix, sorted
+// by value in descending order (NaN last, ties in column order)
+//
+pub(crate) fn inverse_transform_with_weights<T: Clone + Zero + PartialOrd>(feature_names: &[String], matrix: &Array2<T>) -> Vec<Vec<(String, T)>> {
+    assert_eq!(feature_names.len(), matrix.ncols(), "number of columns differs from vocabulary size");
+    matrix.outer_iter()
+        .map(|row| {
+            let mut weights: Vec<(String, T)> = row.iter()
+                .enumerate()
+                .filter(|&(_, e)| !e.is_zero())
+                .map(|(index_col, e)| (feature_names[index_col].clone(), e.clone()))
+                .collect();
+            weights.sort_by(|a, b| ndarray_extension::cmp_nan_last(&b.1, &a.1));
+            weights
+        })
+        .collect()
+}
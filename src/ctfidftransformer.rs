@@ -0,0 +1,135 @@
+use std::vec::Vec;
+use ndarray::{Array1, Array2, Axis};
+use ndarray_extension;
+use naivebayes::encode_labels;
+
+/// Transforms a count matrix (e.g. the output of CountVectorizer) and the
+/// class (or cluster) label of each document into a class-based Tf-Idf
+/// (c-TF-IDF) matrix with a row per class, as ClassTfidfTransformer of
+/// BERTopic. The documents of each class are treated as a single document:
+/// the term frequencies of a class are its summed counts, normalized to sum
+/// to 1, and the weight of a term is idf = ln(1 + A / f), where f is the
+/// frequency of the term over all classes and A the average number of words
+/// per class. Terms frequent in a class but rare overall get the highest
+/// weights, which makes them good labels for the class.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::countvectorizer::CountVectorizer;
+/// use vectorizer::ctfidftransformer::ClassTfidfTransformer;
+///
+/// let docs = vec![
+///     "cat sat on mat",
+///     "cat chased dog",
+///     "stocks fell",
+///     "markets and stocks rallied",
+/// ];
+/// let labels = vec![0, 0, 1, 1];
+///
+/// let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+/// let counts = vectorizer.fit_transform(docs);
+///
+/// let mut transformer = ClassTfidfTransformer::new();
+/// let ctfidf = transformer.fit_transform(&counts, &labels);
+/// assert_eq!(2, ctfidf.dim().0);
+///
+/// let top_terms = transformer.top_terms(&vectorizer.get_feature_names(), 1);
+/// assert_eq!("cat", top_terms[0][0].0);
+/// assert_eq!("stocks", top_terms[1][0].0);
+/// ```
+///
+pub struct ClassTfidfTransformer<L> {
+    /// If true, the square root of the term frequencies is used, which
+    /// reduces the weight of very frequent words. Default is false.
+    pub reduce_frequent_words: bool,
+
+    /// Sorted unique labels seen by fit(), i.e. the class of each row of
+    /// ctfidf_.
+    pub classes_: Vec<L>,
+
+    /// Summed counts of each term in each class (classes × terms).
+    pub class_counts_: Array2<u64>,
+
+    /// Weight of each term, ln(1 + A / f).
+    pub idf_: Array1<f64>,
+
+    /// c-TF-IDF matrix of the training classes (classes × terms).
+    pub ctfidf_: Array2<f64>,
+}
+
+impl<L: Clone + Ord> ClassTfidfTransformer<L> {
+
+    /// Create a new instance of ClassTfidfTransformer with
+    /// reduce_frequent_words=false.
+    ///
+    pub fn new() -> ClassTfidfTransformer<L> {
+        ClassTfidfTransformer {
+            reduce_frequent_words: false,
+            classes_: Vec::new(),
+            class_counts_: Array2::<u64>::zeros((0, 0)),
+            idf_: Array1::<f64>::zeros(0),
+            ctfidf_: Array2::<f64>::zeros((0, 0)),
+        }
+    }
+
+    /// Learn the term weights from a count matrix and the label of each row,
+    /// and compute the c-TF-IDF matrix of the classes (ctfidf_).
+    ///
+    pub fn fit(&mut self, countvector: &Array2<u64>, labels: &[L]) {
+        let (num_rows, num_columns) = countvector.dim();
+        assert_eq!(num_rows, labels.len(), "countvector and labels must have the same length");
+        let (classes, indices) = encode_labels(labels);
+
+        // Join the documents of each class
+        let mut class_counts = Array2::<u64>::zeros((classes.len(), num_columns));
+        for (row, &index_class) in countvector.outer_iter().zip(indices.iter()) {
+            let mut class_row = class_counts.row_mut(index_class);
+            class_row += &row;
+        }
+
+        let frequencies = class_counts.sum_axis(Axis(0)).mapv(|e| e as f64);
+        let average_words = frequencies.sum() / classes.len() as f64;
+        self.idf_ = frequencies.mapv(|f| if f > 0. { (1. + average_words / f).ln() } else { 0. });
+        self.classes_ = classes;
+        self.ctfidf_ = self.transform(&class_counts);
+        self.class_counts_ = class_counts;
+    }
+
+    /// Weight the rows of a count matrix (e.g. classes, or single documents
+    /// to compare with the classes) with the learned term weights. The count
+    /// matrix must have as many columns as the one used for fitting.
+    ///
+    pub fn transform(&self, countvector: &Array2<u64>) -> Array2<f64> {
+        let mut tf = countvector.mapv(|e| e as f64);
+        ndarray_extension::normalize_inplace(&mut tf, "l1", Axis(1), 0.);
+        if self.reduce_frequent_words {
+            tf.mapv_inplace(f64::sqrt);
+        }
+        tf * &self.idf_
+    }
+
+    /// Fit to a count matrix and labels, then return the c-TF-IDF matrix of
+    /// the classes (a row per class of classes_). See fit().
+    ///
+    pub fn fit_transform(&mut self, countvector: &Array2<u64>, labels: &[L]) -> Array2<f64> {
+        self.fit(countvector, labels);
+        self.ctfidf_.clone()
+    }
+
+    /// Terms with the k largest c-TF-IDF weights of each class (in the order
+    /// of classes_) as (term, weight) pairs, in descending order of weight.
+    /// Terms absent from a class have a weight of 0. feature_names are the
+    /// column names, e.g. from CountVectorizer::get_feature_names().
+    ///
+    pub fn top_terms(&self, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>> {
+        ndarray_extension::top_terms(&self.ctfidf_, feature_names, k)
+    }
+}
+
+impl<L: Clone + Ord> Default for ClassTfidfTransformer<L> {
+    fn default() -> ClassTfidfTransformer<L> {
+        ClassTfidfTransformer::new()
+    }
+}
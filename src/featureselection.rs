@@ -0,0 +1,274 @@
+use std::vec::Vec;
+use ndarray::{Array1, Array2, Axis};
+use num::ToPrimitive;
+use ndarray_extension;
+use naivebayes::encode_labels;
+
+// Per class sums of the rows of x (classes × columns) and class sizes
+fn _sum_by_class<T: Clone + ToPrimitive, F: Fn(f64) -> f64>(x: &Array2<T>, indices: &[usize], num_classes: usize, f: F) -> (Array2<f64>, Array1<f64>) {
+    let mut sums = Array2::<f64>::zeros((num_classes, x.dim().1));
+    let mut class_count = Array1::<f64>::zeros(num_classes);
+    for (row, &index_class) in x.outer_iter().zip(indices.iter()) {
+        class_count[index_class] += 1.;
+        let mut class_row = sums.row_mut(index_class);
+        class_row.zip_mut_with(&row, |sum, e| *sum += f(e.to_f64().unwrap()));
+    }
+    (sums, class_count)
+}
+
+/// Chi-squared statistic between each column of a non-negative matrix
+/// (e.g. counts from CountVectorizer or weights from TfidfVectorizer) and
+/// the labels of the rows, as chi2 of scikit-learn. The observed value of a
+/// column in a class is the sum of the column over the rows of the class,
+/// and the expected value assumes that the column is independent of the
+/// labels. Higher scores mean more class-dependent columns. Columns that are
+/// zero everywhere have a score of 0.
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::arr2;
+/// use vectorizer::featureselection;
+///
+/// let x = arr2(&[
+///     [2, 1],
+///     [3, 1],
+///     [0, 1],
+///     [0, 1]]);
+/// let scores = featureselection::chi2(&x, &["a", "a", "b", "b"]);
+///
+/// assert_eq!(5.0, scores[0]);
+/// assert_eq!(0.0, scores[1]);
+/// ```
+///
+pub fn chi2<T: Clone + ToPrimitive, L: Clone + Ord>(x: &Array2<T>, labels: &[L]) -> Array1<f64> {
+    assert_eq!(x.dim().0, labels.len(), "x and labels must have the same length");
+    let (classes, indices) = encode_labels(labels);
+    let (observed, class_count) = _sum_by_class(x, &indices, classes.len(), |e| e);
+
+    let class_prob = class_count / labels.len() as f64;
+    let feature_count = observed.sum_axis(Axis(0));
+    let mut scores = Array1::<f64>::zeros(feature_count.len());
+    for (index_col, score) in scores.iter_mut().enumerate() {
+        for (index_class, p) in class_prob.iter().enumerate() {
+            let expected = p * feature_count[index_col];
+            if expected > 0. {
+                *score += (observed[[index_class, index_col]] - expected).powi(2) / expected;
+            }
+        }
+    }
+    scores
+}
+
+/// Mutual information (in nats) between the presence of each column in a
+/// row (non-zero value) and the labels of the rows. Higher scores mean more
+/// informative columns, and 0 means that presence and labels are
+/// independent.
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::arr2;
+/// use vectorizer::featureselection;
+///
+/// let x = arr2(&[
+///     [1, 1],
+///     [1, 0],
+///     [0, 1],
+///     [0, 0]]);
+/// let scores = featureselection::mutual_info(&x, &["a", "a", "b", "b"]);
+///
+/// assert!((scores[0] - 2f64.ln()).abs() < 1e-12);
+/// assert!(scores[1].abs() < 1e-12);
+/// ```
+///
+pub fn mutual_info<T: Clone + ToPrimitive, L: Clone + Ord>(x: &Array2<T>, labels: &[L]) -> Array1<f64> {
+    assert_eq!(x.dim().0, labels.len(), "x and labels must have the same length");
+    let (classes, indices) = encode_labels(labels);
+    let (present, class_count) = _sum_by_class(x, &indices, classes.len(), |e| (e != 0.) as u8 as f64);
+
+    let n = labels.len() as f64;
+    let num_present = present.sum_axis(Axis(0));
+    let mut scores = Array1::<f64>::zeros(num_present.len());
+    for (index_col, score) in scores.iter_mut().enumerate() {
+        for (index_class, &class_total) in class_count.iter().enumerate() {
+            // (joint count, count of the presence value) for present/absent
+            let joint_present = present[[index_class, index_col]];
+            let cells = [
+                (joint_present, num_present[index_col]),
+                (class_total - joint_present, n - num_present[index_col]),
+            ];
+            for &(joint, marginal) in cells.iter() {
+                if joint > 0. {
+                    *score += joint / n * (joint * n / (marginal * class_total)).ln();
+                }
+            }
+        }
+    }
+    scores
+}
+
+// Scores of the columns by score_func
+fn _score<T: Clone + ToPrimitive, L: Clone + Ord>(score_func: &str, x: &Array2<T>, labels: &[L]) -> Array1<f64> {
+    match score_func {
+        "chi2" => chi2(x, labels),
+        "mutual_info" => mutual_info(x, labels),
+        _ => panic!("unknown score_func: {} (options are \"chi2\" and \"mutual_info\")", score_func),
+    }
+}
+
+// Indices of the k best scores in ascending order
+fn _support(scores: &Array1<f64>, k: usize) -> Vec<usize> {
+    let candidates = scores.iter().cloned().enumerate().collect();
+    let mut support: Vec<usize> = ndarray_extension::top_k(candidates, k).into_iter()
+        .map(|(index_col, _)| index_col)
+        .collect();
+    support.sort_unstable();
+    support
+}
+
+
+/// Struct that keeps the k columns with the highest scores against the
+/// labels, as SelectKBest of scikit-learn. Pass support_ to
+/// CountVectorizer::restrict_vocabulary() (or TfidfVectorizer's) so that the
+/// vectorizer produces the selected columns directly.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::countvectorizer::CountVectorizer;
+/// use vectorizer::featureselection::SelectKBest;
+///
+/// let docs = vec![
+///     "great movie",
+///     "great story",
+///     "bad movie",
+///     "bad story",
+/// ];
+/// let labels = vec!["pos", "pos", "neg", "neg"];
+///
+/// let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+/// let x = vectorizer.fit_transform(docs.clone());
+///
+/// let mut selector = SelectKBest::new("chi2", 2);
+/// let x_selected = selector.fit_transform(&x, &labels);
+///
+/// vectorizer.restrict_vocabulary(&selector.support_);
+/// assert_eq!(vec!["great", "bad"], vectorizer.get_feature_names());
+/// assert_eq!(x_selected, vectorizer.transform(docs));
+/// ```
+///
+pub struct SelectKBest {
+    /// Scoring function. Options are "chi2" and "mutual_info". Panics on any
+    /// other value.
+    pub score_func: String,
+
+    /// Number of columns to keep.
+    pub k: usize,
+
+    /// Score of each column, computed by fit().
+    pub scores_: Array1<f64>,
+
+    /// Indices of the kept columns in ascending order.
+    pub support_: Vec<usize>,
+}
+
+impl SelectKBest {
+
+    /// Create a new instance of SelectKBest.
+    ///
+    pub fn new(score_func: &str, k: usize) -> SelectKBest {
+        SelectKBest {
+            score_func: score_func.to_string(),
+            k,
+            scores_: Array1::<f64>::zeros(0),
+            support_: Vec::new(),
+        }
+    }
+
+    /// Score the columns of x against the labels of its rows and select the
+    /// k best (ties in ascending order of column).
+    ///
+    pub fn fit<T: Clone + ToPrimitive, L: Clone + Ord>(&mut self, x: &Array2<T>, labels: &[L]) {
+        self.scores_ = _score(self.score_func.as_str(), x, labels);
+        self.support_ = _support(&self.scores_, self.k);
+    }
+
+    /// Keep the selected columns of x.
+    ///
+    pub fn transform<T: Copy>(&self, x: &Array2<T>) -> Array2<T> {
+        x.select(Axis(1), &self.support_)
+    }
+
+    /// Fit to x and labels, then transform x. See fit() and transform().
+    ///
+    pub fn fit_transform<T: Copy + ToPrimitive, L: Clone + Ord>(&mut self, x: &Array2<T>, labels: &[L]) -> Array2<T> {
+        self.fit(x, labels);
+        self.transform(x)
+    }
+}
+
+
+/// Struct that keeps the given percentage of columns with the highest scores
+/// against the labels (rounded up), as SelectPercentile of scikit-learn. See
+/// also SelectKBest.
+///
+pub struct SelectPercentile {
+    /// Scoring function. Options are "chi2" and "mutual_info". Panics on any
+    /// other value.
+    pub score_func: String,
+
+    /// Percentage of columns to keep, between 0 and 100.
+    pub percentile: f64,
+
+    /// Score of each column, computed by fit().
+    pub scores_: Array1<f64>,
+
+    /// Indices of the kept columns in ascending order.
+    pub support_: Vec<usize>,
+}
+
+impl SelectPercentile {
+
+    /// Create a new instance of SelectPercentile.
+    ///
+    pub fn new(score_func: &str, percentile: f64) -> SelectPercentile {
+        SelectPercentile {
+            score_func: score_func.to_string(),
+            percentile,
+            scores_: Array1::<f64>::zeros(0),
+            support_: Vec::new(),
+        }
+    }
+
+    /// Score the columns of x against the labels of its rows and select the
+    /// best percentile of them.
+    ///
+    pub fn fit<T: Clone + ToPrimitive, L: Clone + Ord>(&mut self, x: &Array2<T>, labels: &[L]) {
+        self.scores_ = _score(self.score_func.as_str(), x, labels);
+        let num_columns = self.scores_.len();
+        // Divide by 100 in integers, 28. / 100. * 25. is slightly above 7
+        //
+        let scaled = (self.percentile.clamp(0., 100.) * num_columns as f64).ceil() as usize;
+        let k = scaled.div_ceil(100);
+        self.support_ = _support(&self.scores_, k);
+    }
+
+    /// Keep the selected columns of x.
+    ///
+    pub fn transform<T: Copy>(&self, x: &Array2<T>) -> Array2<T> {
+        x.select(Axis(1), &self.support_)
+    }
+
+    /// Fit to x and labels, then transform x. See fit() and transform().
+    ///
+    pub fn fit_transform<T: Copy + ToPrimitive, L: Clone + Ord>(&mut self, x: &Array2<T>, labels: &[L]) -> Array2<T> {
+        self.fit(x, labels);
+        self.transform(x)
+    }
+}
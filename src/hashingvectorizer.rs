@@ -0,0 +1,190 @@
+use std::vec::Vec;
+use std::collections::HashMap;
+use sparse::CsrMatrix;
+use tokenizer::Tokenizer;
+
+
+/// Compute the 32-bit MurmurHash3 (x86_32 variant by Austin Appleby) of a
+/// byte slice with a seed. The result only depends on the bytes and the
+/// seed, so it is identical across platforms and processes.
+///
+/// # Examples
+/// ```
+/// use vectorizer::hashingvectorizer::murmurhash3_32;
+///
+/// assert_eq!(0, murmurhash3_32(b"", 0));
+/// assert_eq!(0x514e28b7, murmurhash3_32(b"", 1));
+/// assert_eq!(0x2e4ff723, murmurhash3_32(
+///     b"The quick brown fox jumps over the lazy dog", 0));
+/// ```
+///
+pub fn murmurhash3_32(key: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mut hash = seed;
+
+    // Body: 4-byte little endian blocks
+    let blocks = key.chunks_exact(4);
+    let tail = blocks.remainder();
+    for block in blocks {
+        let mut k = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+        hash = hash.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+
+    // Tail: remaining 1 to 3 bytes
+    if !tail.is_empty() {
+        let mut k: u32 = 0;
+        for (i, byte) in tail.iter().enumerate() {
+            k ^= (*byte as u32) << (8 * i);
+        }
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash ^= k;
+    }
+
+    // Finalization mix
+    hash ^= key.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+    hash
+}
+
+
+/// Struct that converts a collection of documents (i.e. Vec<&str>) into a
+/// sparse matrix of token occurrences without storing a vocabulary. Each
+/// token is mapped to one of n_features columns by hashing, so the
+/// vectorizer is stateless and two instances with the same settings always
+/// produce the same columns. The basic flow follows HashingVectorizer of
+/// scikit-learn.
+///
+/// A token is hashed by murmurhash3_32() of its UTF-8 bytes with seed, and
+/// the hash h is read as a signed 32-bit integer. The column index is
+/// |h| % n_features and, if alternate_sign is true, the occurrence is
+/// counted as +1 when h >= 0 and -1 otherwise, so that collisions tend to
+/// cancel out rather than accumulate.
+///
+pub struct HashingVectorizer {
+    /// Number of columns of the output matrix, at least 1. Default is 2^20.
+    pub n_features: usize,
+
+    /// A range of n-values for n-grams to be included. For example
+    /// ngram_range: (1, 3) would include uni-, bi-, and tr-grams. See also
+    /// tokenizer::Tokenizer fr details.
+    pub ngram_range: (u32, u32),
+
+    /// The case of the resulting tokens. Default is no conversion. Options
+    /// are "upper" and "lower". Other inputs will use default. See also
+    /// tokenizer::Tokenizer fr details.
+    pub case: String,
+
+    /// If true, the sign of each occurrence is determined by the hash.
+    /// Default is true.
+    pub alternate_sign: bool,
+
+    /// Seed of the hash function. Default is 0.
+    pub seed: u32,
+
+    /// Type of norm used for normalization. Options are "l1", "l2" and
+    /// "none". Default is "l2".
+    pub norm: String,
+}
+
+impl HashingVectorizer {
+
+    /// Create a new instance of HashingVectorizer with n_features=2^20,
+    /// alternate_sign=true, seed=0 and norm="l2". Options can be changed via
+    /// the public fields.
+    ///
+    pub fn new(ngram_range: (u32, u32), case: &str) -> HashingVectorizer {
+        HashingVectorizer {
+            n_features: 1 << 20,
+            ngram_range,
+            case: case.to_string(),
+            alternate_sign: true,
+            seed: 0,
+            norm: "l2".to_string(),
+        }
+    }
+
+    // Column index and signed value of a single token occurrence
+    fn _hash_token(&self, token: &str) -> (usize, f64) {
+        let hash = murmurhash3_32(token.as_bytes(), self.seed) as i32;
+        let index_col = (hash.unsigned_abs() as usize) % self.n_features;
+        let value = if self.alternate_sign && hash < 0 { -1. } else { 1. };
+        (index_col, value)
+    }
+
+    // Normalize the values of a row in place with the norm setting
+    fn _normalize_row(&self, row: &mut [(usize, f64)]) {
+        let norm = match self.norm.as_str() {
+            "l1" => row.iter().map(|&(_, e)| e.abs()).sum::<f64>(),
+            "l2" => row.iter().map(|&(_, e)| e * e).sum::<f64>().sqrt(),
+            _ => return,
+        };
+        if norm > 0. {
+            for element in row.iter_mut() {
+                element.1 /= norm;
+            }
+        }
+    }
+
+    /// Transform the collection of documents into a sparse matrix with
+    /// n_features columns. No fitting is needed. Panics if n_features is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    ///
+    /// use vectorizer::hashingvectorizer::HashingVectorizer;
+    ///
+    /// let docs = vec![
+    ///     "This is the first document.",
+    ///     "This document is the second document.",
+    ///     "And this is the third one.",
+    ///     "Is this the first document?",
+    /// ];
+    ///
+    /// let mut vectorizer = HashingVectorizer::new((1, 1), "lower");
+    /// vectorizer.n_features = 16;
+    /// let x = vectorizer.transform(docs);
+    ///
+    /// assert_eq!((4, 16), x.dim());
+    /// println!("Hashed Vector :\n{:?}", x.to_dense());
+    /// ```
+    ///
+    pub fn transform(&self, docs: Vec<&str>) -> CsrMatrix<f64> {
+        assert!(self.n_features > 0, "n_features must be positive");
+        let tk = Tokenizer::new(self.ngram_range, self.case.as_str());
+        let tokenized_docs = tk.tokenize(docs);
+
+        let mut rows: Vec<Vec<(usize, f64)>> = Vec::with_capacity(tokenized_docs.len());
+        for doc in tokenized_docs {
+            // Accumulate signed counts per column
+            let mut counts: HashMap<usize, f64> = HashMap::new();
+            for token in doc {
+                let (index_col, value) = self._hash_token(token.as_str());
+                *counts.entry(index_col).or_insert(0.) += value;
+            }
+
+            // Collisions may cancel out to zero; those are not stored
+            let mut row: Vec<(usize, f64)> = counts.into_iter()
+                .filter(|&(_, e)| e != 0.)
+                .collect();
+            self._normalize_row(&mut row);
+            rows.push(row);
+        }
+        CsrMatrix::from_rows(rows, self.n_features)
+    }
+
+    /// Same as transform(). Provided for consistency with the other
+    /// vectorizers; nothing is learned from the documents.
+    ///
+    pub fn fit_transform(&self, docs: Vec<&str>) -> CsrMatrix<f64> {
+        self.transform(docs)
+    }
+}
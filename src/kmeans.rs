@@ -0,0 +1,312 @@
+use std::vec::Vec;
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use ndarray_extension;
+use random::Rng;
+
+/// Struct that clusters documents (rows of a dense matrix, e.g. the output
+/// of TfidfVectorizer) into n_clusters groups by k-means with k-means++
+/// initialization. The basic flow follows KMeans and MiniBatchKMeans of
+/// scikit-learn.
+///
+/// With metric "cosine" (spherical k-means), rows are L2-normalized,
+/// documents are assigned to the centroid of highest cosine similarity and
+/// centroids are kept at unit length. With "euclidean", it is the standard
+/// k-means. If batch_size is set, centroids are updated from random
+/// mini-batches of documents (Sculley, 2010), which is much faster on large
+/// corpora at a small cost in quality.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::kmeans::KMeans;
+/// use vectorizer::tfidfvectorizer::TfidfVectorizer;
+///
+/// let docs = vec![
+///     "cannot login to my account",
+///     "login fails with password error",
+///     "refund for my last order",
+///     "order refund not received",
+/// ];
+///
+/// let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+/// let x = vectorizer.fit_transform(docs);
+///
+/// let mut kmeans = KMeans::new(2);
+/// let labels = kmeans.fit_predict(&x);
+///
+/// assert_eq!(labels[0], labels[1]);
+/// assert_eq!(labels[2], labels[3]);
+/// assert_ne!(labels[0], labels[2]);
+/// println!("Top terms: {:?}", kmeans.top_terms(&vectorizer.get_feature_names(), 2));
+/// ```
+///
+pub struct KMeans {
+    /// Number of clusters.
+    pub n_clusters: usize,
+
+    /// Similarity used for the assignments. Options are "cosine" (spherical
+    /// k-means) and "euclidean". Default is "cosine". Panics on any other
+    /// value.
+    pub metric: String,
+
+    /// If Some, the number of documents of each mini-batch; if None, all the
+    /// documents are used at each iteration. Default is None.
+    pub batch_size: Option<usize>,
+
+    /// Maximum number of iterations (passes over the documents with
+    /// mini-batches). Default is 300.
+    pub max_iter: usize,
+
+    /// Iterations stop when the squared centroid shift is smaller than tol.
+    /// Default is 1e-4.
+    pub tol: f64,
+
+    /// Number of runs from different initializations. The run with the
+    /// lowest inertia is kept. Default is 1.
+    pub n_init: usize,
+
+    /// Seed of the initializations and mini-batches. Default is 0.
+    pub seed: u64,
+
+    /// Centroid of each cluster (n_clusters × columns). Computed by fit().
+    pub cluster_centers_: Array2<f64>,
+
+    /// Cluster of each training document.
+    pub labels_: Vec<usize>,
+
+    /// Sum over the training documents of the distance to their centroid:
+    /// the squared Euclidean distance with "euclidean", and 1 - cosine
+    /// similarity with "cosine".
+    pub inertia_: f64,
+
+    /// Number of iterations of the kept run.
+    pub n_iter_: usize,
+}
+
+impl KMeans {
+
+    /// Create a new instance of KMeans with metric="cosine",
+    /// batch_size=None, max_iter=300, tol=1e-4, n_init=1 and seed=0. Options
+    /// can be changed via the public fields.
+    ///
+    pub fn new(n_clusters: usize) -> KMeans {
+        KMeans {
+            n_clusters,
+            metric: "cosine".to_string(),
+            batch_size: None,
+            max_iter: 300,
+            tol: 1e-4,
+            n_init: 1,
+            seed: 0,
+            cluster_centers_: Array2::<f64>::zeros((0, 0)),
+            labels_: Vec::new(),
+            inertia_: 0.,
+            n_iter_: 0,
+        }
+    }
+
+    fn _is_spherical(&self) -> bool {
+        match self.metric.as_str() {
+            "cosine" => true,
+            "euclidean" => false,
+            _ => panic!("unknown metric: {} (options are \"cosine\" and \"euclidean\")", self.metric),
+        }
+    }
+
+    // Documents as used by the metric, i.e. L2-normalized for "cosine"
+    fn _prepare(&self, x: &Array2<f64>) -> Array2<f64> {
+        let mut x = x.clone();
+        if self._is_spherical() {
+            ndarray_extension::normalize_inplace(&mut x, "l2", Axis(1), 0.);
+        }
+        x
+    }
+
+    // Distance between a document and a centroid
+    fn _distance(&self, row: ArrayView1<f64>, center: ArrayView1<f64>) -> f64 {
+        if self._is_spherical() {
+            1. - row.dot(&center)
+        } else {
+            row.iter().zip(center.iter()).map(|(a, b)| (a - b) * (a - b)).sum()
+        }
+    }
+
+    // Closest centroid of each document and the distance to it
+    fn _assign(&self, x: &Array2<f64>, centers: &Array2<f64>) -> (Vec<usize>, Vec<f64>) {
+        x.outer_iter()
+            .map(|row| {
+                centers.outer_iter()
+                    .map(|center| self._distance(row, center))
+                    .enumerate()
+                    .fold((0, f64::INFINITY), |best, (index, distance)| {
+                        if distance < best.1 { (index, distance) } else { best }
+                    })
+            })
+            .unzip()
+    }
+
+    // k-means++: each new centroid is a document drawn with probability
+    // proportional to its distance to the closest centroid so far
+    fn _init_centers(&self, x: &Array2<f64>, rng: &mut Rng) -> Array2<f64> {
+        let num_rows = x.dim().0;
+        let mut centers = Array2::<f64>::zeros((self.n_clusters, x.dim().1));
+        centers.row_mut(0).assign(&x.row(rng.gen_range(num_rows)));
+        let mut closest: Vec<f64> = x.outer_iter()
+            .map(|row| self._distance(row, centers.row(0)).max(0.))
+            .collect();
+
+        for index_center in 1..self.n_clusters {
+            let total: f64 = closest.iter().sum();
+            let index_row = if total > 0. {
+                let mut target = rng.next_f64() * total;
+                closest.iter()
+                    .position(|&d| { target -= d; target < 0. })
+                    .unwrap_or(num_rows - 1)
+            } else {
+                rng.gen_range(num_rows)
+            };
+            centers.row_mut(index_center).assign(&x.row(index_row));
+            for (d, row) in closest.iter_mut().zip(x.outer_iter()) {
+                *d = d.min(self._distance(row, centers.row(index_center)).max(0.));
+            }
+        }
+        centers
+    }
+
+    // Keep centroids at unit length for spherical k-means
+    fn _normalize_centers(&self, centers: &mut Array2<f64>) {
+        if self._is_spherical() {
+            ndarray_extension::normalize_inplace(centers, "l2", Axis(1), 0.);
+        }
+    }
+
+    // Lloyd iterations. Returns the number of iterations.
+    fn _lloyd(&self, x: &Array2<f64>, centers: &mut Array2<f64>) -> usize {
+        for iteration in 1..=self.max_iter {
+            let (labels, _) = self._assign(x, centers);
+            let mut sums = Array2::<f64>::zeros(centers.dim());
+            let mut counts = Array1::<f64>::zeros(self.n_clusters);
+            for (row, &label) in x.outer_iter().zip(labels.iter()) {
+                let mut sum = sums.row_mut(label);
+                sum += &row;
+                counts[label] += 1.;
+            }
+
+            // Empty clusters keep their centroid
+            let mut new_centers = centers.clone();
+            for (index_center, &count) in counts.iter().enumerate() {
+                if count > 0. {
+                    new_centers.row_mut(index_center).assign(&(&sums.row(index_center) / count));
+                }
+            }
+            self._normalize_centers(&mut new_centers);
+
+            let shift: f64 = (&new_centers - &*centers).mapv(|e| e * e).sum();
+            *centers = new_centers;
+            if shift <= self.tol {
+                return iteration;
+            }
+        }
+        self.max_iter
+    }
+
+    // Mini-batch iterations with per-centroid learning rates. Returns the
+    // number of passes.
+    fn _mini_batch(&self, x: &Array2<f64>, centers: &mut Array2<f64>, batch_size: usize, rng: &mut Rng) -> usize {
+        let num_rows = x.dim().0;
+        let batch_size = batch_size.max(1).min(num_rows);
+        let num_batches = num_rows.div_ceil(batch_size);
+        let mut counts = Array1::<f64>::zeros(self.n_clusters);
+
+        for iteration in 1..=self.max_iter {
+            let previous = centers.clone();
+            for _ in 0..num_batches {
+                let indices: Vec<usize> = (0..batch_size).map(|_| rng.gen_range(num_rows)).collect();
+                let batch = x.select(Axis(0), &indices);
+                let (labels, _) = self._assign(&batch, centers);
+                for (row, &label) in batch.outer_iter().zip(labels.iter()) {
+                    counts[label] += 1.;
+                    let eta = 1. / counts[label];
+                    let mut center = centers.row_mut(label);
+                    center *= 1. - eta;
+                    center.scaled_add(eta, &row);
+                }
+                self._normalize_centers(centers);
+            }
+
+            let shift: f64 = (&*centers - &previous).mapv(|e| e * e).sum();
+            if shift <= self.tol {
+                return iteration;
+            }
+        }
+        self.max_iter
+    }
+
+    /// Cluster documents (rows). Panics if n_clusters is 0 or there are
+    /// fewer documents than n_clusters.
+    ///
+    pub fn fit(&mut self, x: &Array2<f64>) {
+        assert!(self.n_clusters > 0, "n_clusters must be positive");
+        let x = self._prepare(x);
+        assert!(x.dim().0 >= self.n_clusters, "fewer documents than clusters");
+
+        let mut rng = Rng::new(self.seed);
+        let mut best: Option<(Array2<f64>, Vec<usize>, f64, usize)> = None;
+        for _ in 0..self.n_init.max(1) {
+            let mut centers = self._init_centers(&x, &mut rng);
+            let num_iter = match self.batch_size {
+                Some(batch_size) => self._mini_batch(&x, &mut centers, batch_size, &mut rng),
+                None => self._lloyd(&x, &mut centers),
+            };
+            let (labels, distances) = self._assign(&x, &centers);
+            let inertia: f64 = distances.iter().sum();
+            let improved = match best {
+                Some(ref b) => inertia < b.2,
+                None => true,
+            };
+            if improved {
+                best = Some((centers, labels, inertia, num_iter));
+            }
+        }
+
+        let (centers, labels, inertia, num_iter) = best.unwrap();
+        self.cluster_centers_ = centers;
+        self.labels_ = labels;
+        self.inertia_ = inertia;
+        self.n_iter_ = num_iter;
+    }
+
+    /// Closest cluster of each document (rows with the columns used by
+    /// fit()).
+    ///
+    pub fn predict(&self, x: &Array2<f64>) -> Vec<usize> {
+        self._assign(&self._prepare(x), &self.cluster_centers_).0
+    }
+
+    /// Fit to documents, then return their clusters. See fit().
+    ///
+    pub fn fit_predict(&mut self, x: &Array2<f64>) -> Vec<usize> {
+        self.fit(x);
+        self.labels_.clone()
+    }
+
+    /// Distance of each document (rows) to each centroid (columns). See
+    /// inertia_ for the distance of each metric.
+    ///
+    pub fn transform(&self, x: &Array2<f64>) -> Array2<f64> {
+        let x = self._prepare(x);
+        let num_rows = x.dim().0;
+        Array2::from_shape_fn((num_rows, self.n_clusters), |(i, j)| {
+            self._distance(x.row(i), self.cluster_centers_.row(j))
+        })
+    }
+
+    /// Terms with the k largest centroid weights of each cluster as (term,
+    /// weight) pairs, in descending order of weight. feature_names are the
+    /// column names, e.g. from TfidfVectorizer::get_feature_names().
+    ///
+    pub fn top_terms(&self, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>> {
+        ndarray_extension::top_terms(&self.cluster_centers_, feature_names, k)
+    }
+}
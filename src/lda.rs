@@ -0,0 +1,422 @@
+use std::f64::consts::PI;
+use std::vec::Vec;
+use ndarray::{Array1, Array2, ArrayView1, Axis};
+use ndarray_extension;
+use random::Rng;
+
+// Small value added to denominators
+const EPSILON: f64 = 1e-100;
+
+// Digamma function (derivative of ln Gamma) for x > 0
+fn _digamma(mut x: f64) -> f64 {
+    let mut result = 0.;
+    while x < 10. {
+        result -= 1. / x;
+        x += 1.;
+    }
+    let inv2 = 1. / (x * x);
+    result + x.ln() - 0.5 / x
+        - inv2 * (1. / 12. - inv2 * (1. / 120. - inv2 * (1. / 252. - inv2 * (1. / 240. - inv2 / 132.))))
+}
+
+// Natural logarithm of the Gamma function for x > 0 (Lanczos approximation)
+fn _ln_gamma(x: f64) -> f64 {
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9, 676.520_368_121_885_1, -1_259.139_216_722_402_8,
+        771.323_428_777_653_1, -176.615_029_162_140_6, 12.507_343_278_686_905,
+        -0.138_571_095_265_720_12, 9.984_369_578_019_572e-6, 1.505_632_735_149_311_6e-7];
+    let x = x - 1.;
+    let t = x + 7.5;
+    let series = COEFFICIENTS[1..].iter()
+        .enumerate()
+        .fold(COEFFICIENTS[0], |sum, (i, c)| sum + c / (x + i as f64 + 1.));
+    0.5 * (2. * PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+// E[log X] for X ~ Dirichlet(alpha), for each row of alpha
+fn _dirichlet_expectation(alpha: &Array2<f64>) -> Array2<f64> {
+    let mut expectation = alpha.mapv(_digamma);
+    for (mut row, alpha_row) in expectation.outer_iter_mut().zip(alpha.outer_iter()) {
+        let digamma_sum = _digamma(alpha_row.sum());
+        row.mapv_inplace(|e| e - digamma_sum);
+    }
+    expectation
+}
+
+// exp(E[log X]) for X ~ Dirichlet(alpha) of a single row
+fn _exp_dirichlet_expectation(alpha: ArrayView1<f64>) -> Array1<f64> {
+    let digamma_sum = _digamma(alpha.sum());
+    alpha.mapv(|e| (_digamma(e) - digamma_sum).exp())
+}
+
+// Column indices and counts of the non-zero elements of a row
+fn _nonzero(counts: ArrayView1<u64>) -> (Vec<usize>, Array1<f64>) {
+    let (ids, cnts): (Vec<usize>, Vec<f64>) = counts.iter()
+        .enumerate()
+        .filter(|&(_, &count)| count > 0)
+        .map(|(id, &count)| (id, count as f64))
+        .unzip();
+    (ids, Array1::from(cnts))
+}
+
+// Part of the variational bound from the Dirichlet priors of a distribution
+fn _log_likelihood(prior: f64, distribution: &Array2<f64>, dirichlet: &Array2<f64>, size: usize) -> f64 {
+    let mut score = distribution.iter()
+        .zip(dirichlet.iter())
+        .map(|(&d, &e)| (prior - d) * e + _ln_gamma(d) - _ln_gamma(prior))
+        .sum::<f64>();
+    for row in distribution.outer_iter() {
+        score += _ln_gamma(prior * size as f64) - _ln_gamma(row.sum());
+    }
+    score
+}
+
+
+/// Struct that learns topics from a count matrix (the output of
+/// CountVectorizer) by Latent Dirichlet Allocation (LDA) with variational
+/// Bayes inference (Hoffman et al., 2010). The basic flow follows
+/// LatentDirichletAllocation of scikit-learn.
+///
+/// With learning_method "batch", each iteration of fit() updates the topics
+/// from all the documents. With "online", the topics are updated after each
+/// mini-batch of batch_size documents, which is what partial_fit() does and
+/// suits corpora that do not fit in memory.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::countvectorizer::CountVectorizer;
+/// use vectorizer::lda::LatentDirichletAllocation;
+///
+/// let docs = vec![
+///     "cat dog cat pet",
+///     "dog cat pet dog",
+///     "stocks markets trade",
+///     "markets stocks stocks",
+/// ];
+///
+/// let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+/// let counts = vectorizer.fit_transform(docs);
+///
+/// let mut lda = LatentDirichletAllocation::new(2);
+/// lda.seed = 1;
+/// let doc_topic = lda.fit_transform(&counts);
+///
+/// assert_eq!((4, 2), doc_topic.dim());
+/// println!("Document-Topic:\n{:?}", doc_topic);
+/// println!("Perplexity: {}", lda.perplexity(&counts));
+/// ```
+///
+pub struct LatentDirichletAllocation {
+    /// Number of topics.
+    pub n_components: usize,
+
+    /// Prior of the document-topic distributions (alpha). If None,
+    /// 1 / n_components is used. Default is None.
+    pub doc_topic_prior: Option<f64>,
+
+    /// Prior of the topic-word distributions (eta). If None, 1 / n_components
+    /// is used. Default is None.
+    pub topic_word_prior: Option<f64>,
+
+    /// Method used by fit(). Options are "batch" and "online". Other inputs
+    /// will use default. Default is "batch".
+    pub learning_method: String,
+
+    /// Exponent controlling how fast older mini-batches are forgotten by the
+    /// online updates, in (0.5, 1.0]. Default is 0.7.
+    pub learning_decay: f64,
+
+    /// Positive offset that downweights the first online updates. Default is
+    /// 10.0.
+    pub learning_offset: f64,
+
+    /// Number of passes over the documents in fit(). Default is 10.
+    pub max_iter: usize,
+
+    /// Number of documents in each online mini-batch. Default is 128.
+    pub batch_size: usize,
+
+    /// Total number of documents of the corpus, used by partial_fit() to
+    /// scale the mini-batch updates. Default is 1e6.
+    pub total_samples: f64,
+
+    /// Tolerance on the mean change of a document-topic distribution that
+    /// stops its update in the E-step. Default is 1e-3.
+    pub mean_change_tol: f64,
+
+    /// Maximum number of updates of a document-topic distribution in the
+    /// E-step. Default is 100.
+    pub max_doc_update_iter: usize,
+
+    /// Seed of the random initialization. Default is 0.
+    pub seed: u64,
+
+    /// Variational parameters of the topic-word distributions (n_components
+    /// × number of columns). Normalizing a row gives the word distribution of
+    /// a topic. Computed by fit() and partial_fit().
+    pub components_: Array2<f64>,
+
+    /// Number of passes run by fit().
+    pub n_iter_: usize,
+
+    /// Number of updates of components_ (EM steps) so far.
+    pub n_batch_iter_: usize,
+
+    // exp(E[log beta]) of components_
+    exp_dirichlet_component: Array2<f64>,
+
+    // Random generator of the initializations
+    rng: Rng,
+}
+
+impl LatentDirichletAllocation {
+
+    /// Create a new instance of LatentDirichletAllocation with the default
+    /// options of the public fields (learning_method="batch", max_iter=10,
+    /// seed=0, ...). Options can be changed via the public fields.
+    ///
+    pub fn new(n_components: usize) -> LatentDirichletAllocation {
+        LatentDirichletAllocation {
+            n_components,
+            doc_topic_prior: None,
+            topic_word_prior: None,
+            learning_method: "batch".to_string(),
+            learning_decay: 0.7,
+            learning_offset: 10.,
+            max_iter: 10,
+            batch_size: 128,
+            total_samples: 1e6,
+            mean_change_tol: 1e-3,
+            max_doc_update_iter: 100,
+            seed: 0,
+            components_: Array2::<f64>::zeros((0, 0)),
+            n_iter_: 0,
+            n_batch_iter_: 0,
+            exp_dirichlet_component: Array2::<f64>::zeros((0, 0)),
+            rng: Rng::new(0),
+        }
+    }
+
+    fn _alpha(&self) -> f64 {
+        self.doc_topic_prior.unwrap_or(1. / self.n_components as f64)
+    }
+
+    fn _eta(&self) -> f64 {
+        self.topic_word_prior.unwrap_or(1. / self.n_components as f64)
+    }
+
+    // Random variational parameters of the given shape
+    fn _random_gamma(&mut self, shape: (usize, usize)) -> Array2<f64> {
+        let rng = &mut self.rng;
+        Array2::from_shape_fn(shape, |_| rng.gamma(100.) / 100.)
+    }
+
+    // Start from random topics for num_columns columns
+    fn _init_latent_vars(&mut self, num_columns: usize) {
+        self.rng = Rng::new(self.seed);
+        self.components_ = self._random_gamma((self.n_components, num_columns));
+        self.exp_dirichlet_component = _dirichlet_expectation(&self.components_).mapv(f64::exp);
+        self.n_iter_ = 0;
+        self.n_batch_iter_ = 1;
+    }
+
+    // Add random topic-word parameters for columns added to the vocabulary
+    fn _extend_columns(&mut self, num_columns: usize) {
+        let num_known_columns = self.components_.dim().1;
+        if num_columns > num_known_columns {
+            let new_columns = self._random_gamma((self.n_components, num_columns - num_known_columns));
+            let mut components = Array2::<f64>::zeros((self.n_components, num_columns));
+            components.slice_mut(s![.., ..num_known_columns]).assign(&self.components_);
+            components.slice_mut(s![.., num_known_columns..]).assign(&new_columns);
+            self.components_ = components;
+            self.exp_dirichlet_component = _dirichlet_expectation(&self.components_).mapv(f64::exp);
+        }
+    }
+
+    // Variational E-step starting from the document-topic parameters
+    // doc_topic. Returns the updated parameters and, if cal_sstats is true,
+    // the sufficient statistics of the topic-word parameters.
+    fn _e_step(&self, x: &Array2<u64>, mut doc_topic: Array2<f64>, cal_sstats: bool) -> (Array2<f64>, Array2<f64>) {
+        let (_, num_columns) = x.dim();
+        assert_eq!(num_columns, self.components_.dim().1,
+            "the count matrix must have the columns used for fitting");
+        let alpha = self._alpha();
+        let mut sstats = if cal_sstats {
+            Array2::<f64>::zeros((self.n_components, num_columns))
+        } else {
+            Array2::<f64>::zeros((0, 0))
+        };
+
+        for (counts, mut gamma) in x.outer_iter().zip(doc_topic.outer_iter_mut()) {
+            let (ids, cnts) = _nonzero(counts);
+            let exp_topic_word = self.exp_dirichlet_component.select(Axis(1), &ids);
+            let mut exp_doc_topic = _exp_dirichlet_expectation(gamma.view());
+            let mut norm_phi = exp_doc_topic.dot(&exp_topic_word) + EPSILON;
+
+            // Iterate the document-topic parameters to a fixed point
+            for _ in 0..self.max_doc_update_iter {
+                let last_gamma = gamma.to_owned();
+                let updated = &exp_doc_topic * &exp_topic_word.dot(&(&cnts / &norm_phi)) + alpha;
+                gamma.assign(&updated);
+                exp_doc_topic = _exp_dirichlet_expectation(gamma.view());
+                norm_phi = exp_doc_topic.dot(&exp_topic_word) + EPSILON;
+                let mean_change = (&last_gamma - &gamma).mapv(f64::abs).mean().unwrap_or(0.);
+                if mean_change < self.mean_change_tol {
+                    break;
+                }
+            }
+
+            if cal_sstats {
+                let ratio = &cnts / &norm_phi;
+                for (id, r) in ids.iter().zip(ratio.iter()) {
+                    sstats.column_mut(*id).scaled_add(*r, &exp_doc_topic);
+                }
+            }
+        }
+
+        if cal_sstats {
+            sstats *= &self.exp_dirichlet_component;
+        }
+        (doc_topic, sstats)
+    }
+
+    // E-step from random parameters followed by the update of components_
+    fn _em_step(&mut self, x: &Array2<u64>, total_samples: f64, online: bool) {
+        let doc_topic_init = self._random_gamma((x.dim().0, self.n_components));
+        let (_, sstats) = self._e_step(x, doc_topic_init, true);
+        let eta = self._eta();
+
+        if online {
+            let weight = (self.learning_offset + self.n_batch_iter_ as f64).powf(-self.learning_decay);
+            let doc_ratio = total_samples / x.dim().0 as f64;
+            self.components_ *= 1. - weight;
+            self.components_.scaled_add(weight, &(sstats * doc_ratio + eta));
+        } else {
+            self.components_ = sstats + eta;
+        }
+        self.exp_dirichlet_component = _dirichlet_expectation(&self.components_).mapv(f64::exp);
+        self.n_batch_iter_ += 1;
+    }
+
+    // Online EM steps over mini-batches of x
+    fn _online_pass(&mut self, x: &Array2<u64>, total_samples: f64) {
+        let batch_size = self.batch_size.max(1);
+        let num_rows = x.dim().0;
+        for start in (0..num_rows).step_by(batch_size) {
+            let batch = x.slice(s![start..(start + batch_size).min(num_rows), ..]).to_owned();
+            self._em_step(&batch, total_samples, true);
+        }
+    }
+
+    /// Learn the topics from a count matrix. Any previously learned topics
+    /// are discarded. See also partial_fit().
+    ///
+    pub fn fit(&mut self, x: &Array2<u64>) {
+        let (num_rows, num_columns) = x.dim();
+        self._init_latent_vars(num_columns);
+        for _ in 0..self.max_iter {
+            if self.learning_method == "online" {
+                self._online_pass(x, num_rows as f64);
+            } else {
+                self._em_step(x, num_rows as f64, false);
+            }
+            self.n_iter_ += 1;
+        }
+    }
+
+    /// Update the topics with a batch of documents by online variational
+    /// Bayes, scaling the batch by total_samples. The count matrix may have
+    /// more columns than the previous ones (e.g. after
+    /// CountVectorizer::partial_fit()), in which case the new columns start
+    /// with random topic-word parameters.
+    ///
+    pub fn partial_fit(&mut self, x: &Array2<u64>) {
+        let num_columns = x.dim().1;
+        if self.components_.is_empty() {
+            self._init_latent_vars(num_columns);
+        }
+        self._extend_columns(num_columns);
+        let total_samples = self.total_samples;
+        self._online_pass(x, total_samples);
+    }
+
+    // Unnormalized document-topic parameters of documents
+    fn _unnormalized_transform(&self, x: &Array2<u64>) -> Array2<f64> {
+        let doc_topic_init = Array2::<f64>::ones((x.dim().0, self.n_components));
+        self._e_step(x, doc_topic_init, false).0
+    }
+
+    /// Topic distribution of each document (each row sums to 1) under the
+    /// learned topics. The count matrix must have the columns used for
+    /// fitting.
+    ///
+    pub fn transform(&self, x: &Array2<u64>) -> Array2<f64> {
+        let mut doc_topic = self._unnormalized_transform(x);
+        ndarray_extension::normalize_inplace(&mut doc_topic, "l1", Axis(1), 0.);
+        doc_topic
+    }
+
+    /// Fit to a count matrix, then transform it. See fit() and transform().
+    ///
+    pub fn fit_transform(&mut self, x: &Array2<u64>) -> Array2<f64> {
+        self.fit(x);
+        self.transform(x)
+    }
+
+    /// Perplexity of documents under the learned topics, exp(-bound / number
+    /// of words), where bound is the variational lower bound of the log
+    /// likelihood. Lower is better.
+    ///
+    pub fn perplexity(&self, x: &Array2<u64>) -> f64 {
+        let doc_topic = self._unnormalized_transform(x);
+        let dirichlet_doc_topic = _dirichlet_expectation(&doc_topic);
+        let dirichlet_component = _dirichlet_expectation(&self.components_);
+
+        // E[log p(words | theta, beta)]
+        let mut score = 0.;
+        for (counts, e_log_theta) in x.outer_iter().zip(dirichlet_doc_topic.outer_iter()) {
+            let (ids, cnts) = _nonzero(counts);
+            for (id, count) in ids.iter().zip(cnts.iter()) {
+                let temp = &e_log_theta + &dirichlet_component.column(*id);
+                let max = temp.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+                let log_sum_exp = max + temp.mapv(|e| (e - max).exp()).sum().ln();
+                score += count * log_sum_exp;
+            }
+        }
+
+        // Dirichlet priors of the document-topic and topic-word distributions
+        score += _log_likelihood(self._alpha(), &doc_topic, &dirichlet_doc_topic, self.n_components);
+        score += _log_likelihood(self._eta(), &self.components_, &dirichlet_component, self.components_.dim().1);
+
+        let word_count = x.iter().sum::<u64>() as f64;
+        (-score / word_count).exp()
+    }
+
+    /// Words with the k largest weights of each topic as (word, weight)
+    /// pairs, in descending order of weight. feature_names are the column
+    /// names, e.g. from CountVectorizer::get_feature_names().
+    ///
+    pub fn top_terms(&self, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>> {
+        ndarray_extension::top_terms(&self.components_, feature_names, k)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_special_functions() {
+        // digamma(1) = -Euler–Mascheroni constant, digamma(x + 1) = digamma(x) + 1 / x
+        assert!((_digamma(1.) + 0.577_215_664_901_532_9).abs() < 1e-12);
+        assert!((_digamma(0.3) + 1. / 0.3 - _digamma(1.3)).abs() < 1e-12);
+
+        // ln Gamma(n) = ln (n - 1)!
+        assert!(_ln_gamma(1.).abs() < 1e-12);
+        assert!((_ln_gamma(5.) - 24f64.ln()).abs() < 1e-12);
+        assert!((_ln_gamma(0.5) - PI.sqrt().ln()).abs() < 1e-12);
+    }
+}
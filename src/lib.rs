@@ -2,6 +2,14 @@
 extern crate ndarray;
 extern crate regex;
 extern crate num;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+extern crate serde_json;
+#[cfg(feature = "serde")]
+extern crate bincode;
 // extern crate indexmap;
 
 /// tokenization of &str documents.
@@ -20,3 +28,82 @@ pub mod countvectorizer;
 /// 
 pub mod tfidfvectorizer;
 
+/// Td-Idf transformer module (count matrix to Tf-Idf matrix)
+/// 
+pub mod tfidftransformer;
+
+/// Sparse matrix (CSR format) module
+/// 
+pub mod sparse;
+
+/// Hashing vectorizer module
+/// 
+pub mod hashingvectorizer;
+
+/// Save/load module for fitted vectorizers
+/// 
+#[cfg(feature = "serde")]
+pub mod serialization;
+
+
+/// Linear algebra module (randomized SVD)
+/// 
+pub mod linalg;
+
+/// Latent Semantic Analysis module (truncated SVD)
+/// 
+pub mod lsa;
+
+/// Non-negative matrix factorization module (topic model)
+/// 
+pub mod nmf;
+
+/// Latent Dirichlet Allocation module (topic model)
+/// 
+pub mod lda;
+
+/// Naive Bayes classifier module
+/// 
+pub mod naivebayes;
+
+/// Linear classifier module (logistic regression and linear SVM)
+/// 
+pub mod linearmodel;
+
+/// K-means clustering module
+/// 
+pub mod kmeans;
+
+/// Feature selection module (chi-squared and mutual information)
+/// 
+pub mod featureselection;
+
+/// Class-based Tf-Idf (c-TF-IDF) module
+/// 
+pub mod ctfidftransformer;
+
+/// Supervised term weighting module (delta TF-IDF and TF-RF)
+/// 
+pub mod supervisedtransformer;
+
+/// Log-entropy weighting module
+/// 
+pub mod logentropytransformer;
+
+/// Search module (inverted index with BM25 or Tf-Idf ranking)
+/// 
+pub mod search;
+
+/// MinHash and locality-sensitive hashing module
+/// 
+pub mod minhash;
+
+/// SimHash fingerprint module
+/// 
+pub mod simhash;
+
+/// Word co-occurrence and PPMI word vector module
+/// 
+pub mod cooccurrence;
+
+mod random;
@@ -0,0 +1,218 @@
+use ndarray::{Array1, Array2, ArrayView2, Axis};
+use random::Rng;
+use sparse::CsrMatrix;
+
+/// A matrix that can be multiplied with dense matrices. Implemented for
+/// dense Array2<f64> and sparse CsrMatrix<f64>, so that the decompositions
+/// (e.g. lsa::TruncatedSvd) accept the output of any vectorizer.
+///
+pub trait LinearOperator {
+    /// Shape of the matrix as (number of rows, number of columns).
+    fn shape(&self) -> (usize, usize);
+
+    /// Product with a dense matrix, self · other.
+    fn dot(&self, other: ArrayView2<f64>) -> Array2<f64>;
+
+    /// Product of the transposed matrix with a dense matrix, self^T · other.
+    fn transpose_dot(&self, other: ArrayView2<f64>) -> Array2<f64>;
+
+    /// Sum of the squares of all the elements (squared Frobenius norm).
+    fn sum_of_squares(&self) -> f64;
+}
+
+impl LinearOperator for Array2<f64> {
+    fn shape(&self) -> (usize, usize) {
+        self.dim()
+    }
+
+    fn dot(&self, other: ArrayView2<f64>) -> Array2<f64> {
+        Array2::dot(self, &other)
+    }
+
+    fn transpose_dot(&self, other: ArrayView2<f64>) -> Array2<f64> {
+        self.t().dot(&other)
+    }
+
+    fn sum_of_squares(&self) -> f64 {
+        self.iter().map(|e| e * e).sum()
+    }
+}
+
+impl LinearOperator for CsrMatrix<f64> {
+    fn shape(&self) -> (usize, usize) {
+        self.dim()
+    }
+
+    fn dot(&self, other: ArrayView2<f64>) -> Array2<f64> {
+        CsrMatrix::dot(self, &other)
+    }
+
+    fn transpose_dot(&self, other: ArrayView2<f64>) -> Array2<f64> {
+        CsrMatrix::transpose_dot(self, &other)
+    }
+
+    fn sum_of_squares(&self) -> f64 {
+        self.data.iter().map(|e| e * e).sum()
+    }
+}
+
+
+// Orthonormalize the columns of a matrix in place by modified Gram-Schmidt,
+// applied twice for numerical stability. Columns that are (numerically)
+// linear combinations of the previous ones are set to zero.
+fn _orthonormalize(a: &mut Array2<f64>) {
+    let (_, num_columns) = a.dim();
+    for _ in 0..2 {
+        for index_col in 0..num_columns {
+            let (previous, mut rest) = a.view_mut().split_at(Axis(1), index_col);
+            let mut column = rest.column_mut(0);
+            let original_norm = column.dot(&column).sqrt();
+            for previous_column in previous.gencolumns() {
+                let projection = previous_column.dot(&column);
+                column.scaled_add(-projection, &previous_column);
+            }
+            let norm = column.dot(&column).sqrt();
+            if norm > 1e-10 * original_norm {
+                column.mapv_inplace(|e| e / norm);
+            } else {
+                column.fill(0.);
+            }
+        }
+    }
+}
+
+// Singular value decomposition a = u · diag(s) · v^T of a (small) matrix with
+// at least as many rows as columns by one-sided Jacobi rotations. Singular
+// values are sorted in descending order.
+fn _jacobi_svd(mut a: Array2<f64>) -> (Array2<f64>, Array1<f64>, Array2<f64>) {
+    let (num_rows, num_columns) = a.dim();
+    let mut v = Array2::<f64>::eye(num_columns);
+
+    for _sweep in 0..100 {
+        let mut rotated = false;
+        for p in 0..num_columns {
+            for q in (p + 1)..num_columns {
+                let alpha = a.column(p).dot(&a.column(p));
+                let beta = a.column(q).dot(&a.column(q));
+                let gamma = a.column(p).dot(&a.column(q));
+                if gamma.abs() <= 1e-15 * (alpha * beta).sqrt() || gamma == 0. {
+                    continue;
+                }
+                rotated = true;
+
+                // Rotation that makes columns p and q orthogonal
+                let zeta = (beta - alpha) / (2. * gamma);
+                let t = zeta.signum() / (zeta.abs() + (1. + zeta * zeta).sqrt());
+                let c = 1. / (1. + t * t).sqrt();
+                let s = c * t;
+                for matrix in [&mut a, &mut v].iter_mut() {
+                    for index_row in 0..matrix.dim().0 {
+                        let e_p = matrix[[index_row, p]];
+                        let e_q = matrix[[index_row, q]];
+                        matrix[[index_row, p]] = c * e_p - s * e_q;
+                        matrix[[index_row, q]] = s * e_p + c * e_q;
+                    }
+                }
+            }
+        }
+        if !rotated {
+            break;
+        }
+    }
+
+    // Singular values are the norms of the rotated columns
+    let norms: Vec<f64> = a.gencolumns().into_iter()
+        .map(|column| column.dot(&column).sqrt())
+        .collect();
+    let mut order: Vec<usize> = (0..num_columns).collect();
+    order.sort_by(|&i, &j| norms[j].partial_cmp(&norms[i]).unwrap());
+
+    let mut u = Array2::<f64>::zeros((num_rows, num_columns));
+    let mut v_sorted = Array2::<f64>::zeros((num_columns, num_columns));
+    let mut s = Array1::<f64>::zeros(num_columns);
+    for (index_new, &index_old) in order.iter().enumerate() {
+        s[index_new] = norms[index_old];
+        if norms[index_old] > 0. {
+            u.column_mut(index_new).assign(&(&a.column(index_old) / norms[index_old]));
+        }
+        v_sorted.column_mut(index_new).assign(&v.column(index_old));
+    }
+    (u, s, v_sorted)
+}
+
+/// Compute a rank-k truncated singular value decomposition x ≈ u · diag(s) ·
+/// vt by randomized SVD (Halko et al., 2011). x is projected onto
+/// n_components + n_oversamples random gaussian directions drawn from seed,
+/// the range found is refined by n_iter power iterations, and the SVD of the
+/// small projected matrix is computed exactly. Returns u (rows × k), s (k,
+/// descending) and vt (k × columns). Signs are fixed so that the largest
+/// absolute element of each row of vt is positive, which makes the result
+/// deterministic for a given seed.
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::arr2;
+/// use vectorizer::linalg;
+///
+/// let x = arr2(&[
+///     [3.0, 0.0],
+///     [0.0, 2.0],
+///     [0.0, 0.0]]);
+/// let (u, s, vt) = linalg::randomized_svd(&x, 2, 10, 2, 0);
+///
+/// assert!((s[0] - 3.0).abs() < 1e-10 && (s[1] - 2.0).abs() < 1e-10);
+/// assert!((vt[[0, 0]] - 1.0).abs() < 1e-10);
+/// assert!((u[[1, 1]] - 1.0).abs() < 1e-10);
+/// ```
+///
+pub fn randomized_svd<M: LinearOperator>(
+    x: &M,
+    n_components: usize,
+    n_oversamples: usize,
+    n_iter: usize,
+    seed: u64,
+) -> (Array2<f64>, Array1<f64>, Array2<f64>) {
+    let (num_rows, num_columns) = x.shape();
+    assert!(n_components <= num_rows.min(num_columns),
+        "n_components must not exceed the number of rows or columns");
+    let num_random = (n_components + n_oversamples).min(num_columns);
+
+    // Orthonormal basis of the range of x, refined by power iterations
+    let mut rng = Rng::new(seed);
+    let omega = Array2::from_shape_fn((num_columns, num_random), |_| rng.normal());
+    let mut q = x.dot(omega.view());
+    _orthonormalize(&mut q);
+    for _ in 0..n_iter {
+        let mut z = x.transpose_dot(q.view());
+        _orthonormalize(&mut z);
+        q = x.dot(z.view());
+        _orthonormalize(&mut q);
+    }
+
+    // b = q^T · x, decomposed through the QR decomposition of b^T
+    let b_t = x.transpose_dot(q.view());
+    let mut q_b = b_t.clone();
+    _orthonormalize(&mut q_b);
+    let r = q_b.t().dot(&b_t);
+    let (u_r, s, v_r) = _jacobi_svd(r);
+
+    // x ≈ q · b = (q · v_r) · diag(s) · (q_b · u_r)^T
+    let mut u = q.dot(&v_r.slice(s![.., ..n_components]));
+    let mut vt = q_b.dot(&u_r.slice(s![.., ..n_components])).reversed_axes();
+    let s = s.slice(s![..n_components]).to_owned();
+
+    for index_component in 0..n_components {
+        let mut row = vt.row_mut(index_component);
+        let largest = row.iter()
+            .cloned()
+            .fold(0., |a: f64, b: f64| if b.abs() > a.abs() { b } else { a });
+        if largest < 0. {
+            row.mapv_inplace(|e| -e);
+            u.column_mut(index_component).mapv_inplace(|e| -e);
+        }
+    }
+    (u, s, vt)
+}
@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::vec::Vec;
+use ndarray::{Array1, Array2, Axis};
+use ndarray_extension;
+use random::Rng;
+use sparse::CsrMatrix;
+
+/// Class weights inversely proportional to class frequencies,
+/// n_samples / (n_classes * count), as "balanced" of scikit-learn. Useful as
+/// SgdClassifier::class_weight for imbalanced labels.
+///
+/// # Examples
+/// ```
+/// use vectorizer::linearmodel::balanced_class_weight;
+///
+/// let weights = balanced_class_weight(&["a", "a", "a", "b"]);
+/// assert_eq!(4. / 6., weights["a"]);
+/// assert_eq!(2., weights["b"]);
+/// ```
+///
+pub fn balanced_class_weight<L: Clone + Eq + Hash>(labels: &[L]) -> HashMap<L, f64> {
+    let mut counts: HashMap<L, f64> = HashMap::new();
+    for label in labels {
+        *counts.entry(label.clone()).or_insert(0.) += 1.;
+    }
+    let num_classes = counts.len() as f64;
+    let num_samples = labels.len() as f64;
+    counts.into_iter()
+        .map(|(label, count)| (label, num_samples / (num_classes * count)))
+        .collect()
+}
+
+// Dot product of a sparse row with a dense vector
+fn _sparse_dot(indices: &[usize], data: &[f64], w: &Array1<f64>) -> f64 {
+    indices.iter().zip(data.iter()).map(|(&j, &e)| w[j] * e).sum()
+}
+
+
+/// Linear classifier trained by stochastic gradient descent (SGD) on a sparse
+/// document-term matrix, e.g. a TfidfVectorizer output converted with
+/// CsrMatrix::from_dense() or a HashingVectorizer output. With loss "log" it
+/// is a logistic regression, and with loss "hinge" a linear support vector
+/// machine. The basic flow follows SGDClassifier of scikit-learn.
+///
+/// More than two classes are handled one-vs-rest: one binary classifier is
+/// trained per class, and the class with the largest decision value wins.
+/// With two classes, a single classifier is trained for the second class of
+/// classes_.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::linearmodel::SgdClassifier;
+/// use vectorizer::sparse::CsrMatrix;
+/// use vectorizer::tfidfvectorizer::TfidfVectorizer;
+///
+/// let docs = vec![
+///     "book a flight to paris",
+///     "flight tickets to rome",
+///     "what is the weather today",
+///     "will it rain tomorrow",
+/// ];
+/// let labels = vec!["travel", "travel", "weather", "weather"];
+///
+/// let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+/// let x = CsrMatrix::from_dense(&vectorizer.fit_transform(docs));
+///
+/// let mut classifier = SgdClassifier::new();
+/// classifier.fit(&x, &labels);
+///
+/// let x_new = CsrMatrix::from_dense(&vectorizer.transform(vec!["a flight to rome", "rain today"]));
+/// assert_eq!(vec!["travel", "weather"], classifier.predict(&x_new));
+///
+/// let top = classifier.top_features(&vectorizer.get_feature_names(), 3);
+/// println!("Top features of \"weather\": {:?}", top[0]);
+/// ```
+///
+pub struct SgdClassifier<L> {
+    /// Loss function. Options are "log" (logistic regression) and "hinge"
+    /// (linear SVM). Other inputs will use default. Default is "hinge".
+    pub loss: String,
+
+    /// Regularization. Options are "l2", "l1" and "none". Other inputs will
+    /// use default. Default is "l2".
+    pub penalty: String,
+
+    /// Regularization strength. Also sets the learning rate, which decreases
+    /// as eta0 / (1 + alpha * eta0 * t) over the updates t. Default is 1e-4.
+    pub alpha: f64,
+
+    /// Initial learning rate. Default is 1.0.
+    pub eta0: f64,
+
+    /// Maximum number of passes (epochs) over the training documents.
+    /// Default is 100.
+    pub max_iter: usize,
+
+    /// Training stops when the average loss of an epoch improves by less
+    /// than tol. Default is 1e-4.
+    pub tol: f64,
+
+    /// Weight of the documents of each class. Classes missing from the map
+    /// have a weight of 1.0. If None, all weights are 1.0. See also
+    /// balanced_class_weight(). Default is None.
+    pub class_weight: Option<HashMap<L, f64>>,
+
+    /// Seed of the shuffling of the documents at each epoch. Default is 0.
+    pub seed: u64,
+
+    /// Sorted unique labels seen by fit().
+    pub classes_: Vec<L>,
+
+    /// Coefficient of each feature (binary classifiers × features). There is
+    /// one row for two classes, and one row per class otherwise.
+    pub coef_: Array2<f64>,
+
+    /// Intercept of each binary classifier.
+    pub intercept_: Array1<f64>,
+
+    /// Number of epochs run by fit() (the largest over the binary
+    /// classifiers).
+    pub n_iter_: usize,
+}
+
+impl<L: Clone + Ord + Hash> SgdClassifier<L> {
+
+    /// Create a new instance of SgdClassifier with loss="hinge",
+    /// penalty="l2", alpha=1e-4, eta0=1.0, max_iter=100, tol=1e-4,
+    /// class_weight=None and seed=0. Options can be changed via the public
+    /// fields.
+    ///
+    pub fn new() -> SgdClassifier<L> {
+        SgdClassifier {
+            loss: "hinge".to_string(),
+            penalty: "l2".to_string(),
+            alpha: 1e-4,
+            eta0: 1.,
+            max_iter: 100,
+            tol: 1e-4,
+            class_weight: None,
+            seed: 0,
+            classes_: Vec::new(),
+            coef_: Array2::<f64>::zeros((0, 0)),
+            intercept_: Array1::<f64>::zeros(0),
+            n_iter_: 0,
+        }
+    }
+
+    // Loss and its derivative with respect to the decision value p for a
+    // target y of -1 or +1
+    fn _loss(&self, p: f64, y: f64) -> (f64, f64) {
+        let z = p * y;
+        if self.loss == "log" {
+            // ln(1 + exp(-z)), computed without overflow
+            let loss = if z > 0. { (-z).exp().ln_1p() } else { -z + z.exp().ln_1p() };
+            (loss, -y / (1. + z.exp()))
+        } else if z < 1. {
+            (1. - z, -y)
+        } else {
+            (0., 0.)
+        }
+    }
+
+    // Train a binary classifier on targets of -1 or +1. Returns the
+    // coefficients, the intercept and the number of epochs.
+    fn _fit_binary(&self, x: &CsrMatrix<f64>, y: &[f64], sample_weights: &[f64]) -> (Array1<f64>, f64, usize) {
+        let (num_rows, num_columns) = x.dim();
+        let mut rng = Rng::new(self.seed);
+        let mut order: Vec<usize> = (0..num_rows).collect();
+
+        // w_actual = scale * w, so that L2 decay is O(1) per update
+        let mut w = Array1::<f64>::zeros(num_columns);
+        let mut scale = 1.;
+        let mut intercept = 0.;
+
+        // Cumulative L1 penalty (Tsuruoka et al., 2009)
+        let l1 = self.penalty == "l1";
+        let l2 = !l1 && self.penalty != "none";
+        let mut u = 0.;
+        let mut q = Array1::<f64>::zeros(num_columns);
+
+        let mut t = 0.;
+        let mut previous_loss = f64::INFINITY;
+        let mut num_epochs = 0;
+        for _ in 0..self.max_iter {
+            num_epochs += 1;
+            rng.shuffle(&mut order);
+            let mut total_loss = 0.;
+
+            for &index_row in order.iter() {
+                let (indices, data) = x.row(index_row);
+                let eta = self.eta0 / (1. + self.alpha * self.eta0 * t);
+                t += 1.;
+
+                let p = scale * _sparse_dot(indices, data, &w) + intercept;
+                let (loss, dloss) = self._loss(p, y[index_row]);
+                total_loss += sample_weights[index_row] * loss;
+
+                if l2 {
+                    scale *= 1. - eta * self.alpha;
+                    if scale < 1e-9 {
+                        w *= scale;
+                        scale = 1.;
+                    }
+                }
+                let step = -eta * dloss * sample_weights[index_row];
+                if step != 0. {
+                    for (&j, &e) in indices.iter().zip(data.iter()) {
+                        w[j] += step * e / scale;
+                    }
+                    intercept += step;
+                }
+
+                if l1 {
+                    u += eta * self.alpha;
+                    for &j in indices {
+                        let z = w[j];
+                        if z > 0. {
+                            w[j] = (z - (u + q[j])).max(0.);
+                        } else if z < 0. {
+                            w[j] = (z + (u - q[j])).min(0.);
+                        }
+                        q[j] += w[j] - z;
+                    }
+                }
+            }
+
+            let average_loss = total_loss / num_rows as f64;
+            if previous_loss - average_loss < self.tol {
+                break;
+            }
+            previous_loss = average_loss;
+        }
+        (w * scale, intercept, num_epochs)
+    }
+
+    /// Train on documents (rows of a sparse matrix) and their labels.
+    ///
+    pub fn fit(&mut self, x: &CsrMatrix<f64>, labels: &[L]) {
+        let (num_rows, num_columns) = x.dim();
+        assert_eq!(num_rows, labels.len(), "x and labels must have the same length");
+        let mut classes = labels.to_vec();
+        classes.sort();
+        classes.dedup();
+        assert!(classes.len() >= 2, "at least two classes are needed");
+
+        let sample_weights: Vec<f64> = labels.iter()
+            .map(|label| match self.class_weight {
+                Some(ref weights) => *weights.get(label).unwrap_or(&1.),
+                None => 1.,
+            })
+            .collect();
+
+        // One binary classifier per class, or one for the second class
+        let positive_classes: Vec<L> = if classes.len() == 2 {
+            vec![classes[1].clone()]
+        } else {
+            classes.clone()
+        };
+        self.coef_ = Array2::<f64>::zeros((positive_classes.len(), num_columns));
+        self.intercept_ = Array1::<f64>::zeros(positive_classes.len());
+        self.n_iter_ = 0;
+        for (index_classifier, positive) in positive_classes.iter().enumerate() {
+            let y: Vec<f64> = labels.iter()
+                .map(|label| if label == positive { 1. } else { -1. })
+                .collect();
+            let (w, intercept, num_epochs) = self._fit_binary(x, &y, &sample_weights);
+            self.coef_.row_mut(index_classifier).assign(&w);
+            self.intercept_[index_classifier] = intercept;
+            self.n_iter_ = self.n_iter_.max(num_epochs);
+        }
+        self.classes_ = classes;
+    }
+
+    /// Decision value of each binary classifier (columns) for each document
+    /// (rows), i.e. x · coef_^T + intercept_.
+    ///
+    pub fn decision_function(&self, x: &CsrMatrix<f64>) -> Array2<f64> {
+        x.dot(&self.coef_.t()) + &self.intercept_
+    }
+
+    /// Most likely label of each document.
+    ///
+    pub fn predict(&self, x: &CsrMatrix<f64>) -> Vec<L> {
+        let decision = self.decision_function(x);
+        decision.outer_iter()
+            .map(|row| {
+                if self.classes_.len() == 2 {
+                    self.classes_[(row[0] > 0.) as usize].clone()
+                } else {
+                    let candidates = row.iter().cloned().enumerate().collect();
+                    let (index_class, _) = ndarray_extension::top_k(candidates, 1)[0];
+                    self.classes_[index_class].clone()
+                }
+            })
+            .collect()
+    }
+
+    /// Probability of each class (columns in the order of classes_) for each
+    /// document, from the logistic function of the decision values
+    /// (normalized over the classes when there are more than two). Only
+    /// meaningful with loss "log".
+    ///
+    pub fn predict_proba(&self, x: &CsrMatrix<f64>) -> Array2<f64> {
+        let probabilities = self.decision_function(x).mapv(|e| 1. / (1. + (-e).exp()));
+        if self.classes_.len() == 2 {
+            let num_rows = probabilities.dim().0;
+            Array2::from_shape_fn((num_rows, 2), |(i, j)| {
+                if j == 1 { probabilities[[i, 0]] } else { 1. - probabilities[[i, 0]] }
+            })
+        } else {
+            let mut probabilities = probabilities;
+            ndarray_extension::normalize_inplace(&mut probabilities, "l1", Axis(1), 0.);
+            probabilities
+        }
+    }
+
+    /// Features with the k largest coefficients of each binary classifier
+    /// (rows of coef_) as (feature name, coefficient) pairs, in descending
+    /// order. With two classes, there is a single list for the second class
+    /// of classes_. feature_names are the column names, e.g. from
+    /// TfidfVectorizer::get_feature_names().
+    ///
+    pub fn top_features(&self, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>> {
+        self.coef_.outer_iter()
+            .map(|coefficients| {
+                let candidates = coefficients.iter().cloned().enumerate().collect();
+                ndarray_extension::top_k(candidates, k).into_iter()
+                    .map(|(index_col, coefficient)| (feature_names[index_col].clone(), coefficient))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Coefficient of a feature (by name) in each binary classifier, or None
+    /// if the name is not among feature_names.
+    ///
+    pub fn coefficient(&self, feature_names: &[String], name: &str) -> Option<Array1<f64>> {
+        feature_names.iter()
+            .position(|feature| feature == name)
+            .map(|index_col| self.coef_.column(index_col).to_owned())
+    }
+}
+
+impl<L: Clone + Ord + Hash> Default for SgdClassifier<L> {
+    fn default() -> SgdClassifier<L> {
+        SgdClassifier::new()
+    }
+}
@@ -0,0 +1,135 @@
+use ndarray::{Array1, Array2, Axis};
+use ndarray_extension;
+use tfidftransformer;
+
+/// Transforms a count matrix (e.g. the output of CountVectorizer) into a
+/// log-entropy weighted matrix, the usual weighting before LSA (see
+/// lsa::TruncatedSvd). Each row is a document and each column a token. The
+/// local weight of a count tf is ln(1 + tf), and the global weight of a
+/// column is
+///
+/// g = 1 + Σ_d p_d ln(p_d) / ln(n)
+///
+/// where p_d is the share of the column's total count found in document d
+/// and n the number of documents. Columns concentrated in few documents get
+/// weights near 1, and columns spread evenly over all the documents get
+/// weights near 0.
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::arr2;
+/// use vectorizer::logentropytransformer::LogEntropyTransformer;
+///
+/// let counts = arr2(&[
+///     [1, 2, 0],
+///     [1, 0, 0],
+///     [1, 0, 3]]);
+///
+/// let mut transformer = LogEntropyTransformer::new();
+/// let x = transformer.fit_transform(&counts);
+///
+/// // The first column is in every document with the same count
+/// assert!(transformer.global_weights_[0].abs() < 1e-12);
+/// assert_eq!(1.0, transformer.global_weights_[1]);
+/// assert_eq!(4f64.ln(), x[[2, 2]]);
+/// ```
+///
+pub struct LogEntropyTransformer {
+    /// Type of norm used for normalization. Options are "l1", "l2", "max",
+    /// "pivoted" and "none". Default is "none". See pivot and slope for
+    /// "pivoted", and tfidftransformer::TfidfTransformer for details.
+    pub norm: String,
+
+    /// Pivot used by the "pivoted" norm. If None, the pivot is learned at fit
+    /// time as the average number of unique terms per document. Default is
+    /// None.
+    pub pivot: Option<f64>,
+
+    /// Slope used by the "pivoted" norm. Default is 0.2.
+    pub slope: f64,
+
+    /// Global (entropy) weight of each column, computed by fit().
+    pub global_weights_: Array1<f64>,
+
+    /// Number of documents (rows) seen by fit().
+    pub n_samples_: u64,
+
+    /// Pivot actually used by the "pivoted" norm, i.e. the supplied pivot or
+    /// the one learned by fit().
+    pub pivot_: f64,
+}
+
+impl LogEntropyTransformer {
+
+    /// Create a new instance of LogEntropyTransformer with norm="none",
+    /// pivot=None and slope=0.2.
+    ///
+    pub fn new() -> LogEntropyTransformer {
+        LogEntropyTransformer {
+            norm: "none".to_string(),
+            pivot: None,
+            slope: 0.2,
+            global_weights_: Array1::<f64>::zeros(0),
+            n_samples_: 0,
+            pivot_: 0.,
+        }
+    }
+
+    /// Learn the global weights (and the pivot of the "pivoted" norm if it
+    /// is not supplied) from a count matrix. With a single document, all the
+    /// global weights are 1.
+    ///
+    pub fn fit(&mut self, countvector: &Array2<u64>) {
+        let counts = countvector.mapv(|e| e as f64);
+        let global_frequency = counts.sum_axis(Axis(0));
+        let num_rows = counts.dim().0;
+
+        // Σ_d p_d ln(p_d), zero counts contributing 0
+        let mut entropy = Array1::<f64>::zeros(global_frequency.len());
+        for row in counts.outer_iter() {
+            for ((h, &tf), &gf) in entropy.iter_mut().zip(row.iter()).zip(global_frequency.iter()) {
+                if tf > 0. {
+                    let p = tf / gf;
+                    *h += p * p.ln();
+                }
+            }
+        }
+
+        let log_n = (num_rows as f64).ln();
+        self.global_weights_ = if num_rows > 1 {
+            entropy / log_n + 1.
+        } else {
+            Array1::<f64>::ones(global_frequency.len())
+        };
+        self.n_samples_ = num_rows as u64;
+
+        let df_sum = ndarray_extension::bincount(countvector, Axis(0)).sum();
+        self.pivot_ = tfidftransformer::learned_pivot(self.pivot, df_sum, self.n_samples_);
+    }
+
+    /// Transform a count matrix into a log-entropy matrix using the global
+    /// weights learned by fit(). The count matrix must have as many columns
+    /// as the one used for fitting.
+    ///
+    pub fn transform(&self, countvector: &Array2<u64>) -> Array2<f64> {
+        let mut x = countvector.mapv(|e| (e as f64).ln_1p());
+        x *= &self.global_weights_;
+        tfidftransformer::normalize(x, self.norm.as_str(), self.pivot_, self.slope)
+    }
+
+    /// Fit to a count matrix, then transform it. See fit() and transform().
+    ///
+    pub fn fit_transform(&mut self, countvector: &Array2<u64>) -> Array2<f64> {
+        self.fit(countvector);
+        self.transform(countvector)
+    }
+}
+
+impl Default for LogEntropyTransformer {
+    fn default() -> LogEntropyTransformer {
+        LogEntropyTransformer::new()
+    }
+}
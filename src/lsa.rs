@@ -0,0 +1,147 @@
+use std::vec::Vec;
+use ndarray::{Array1, Array2, Axis};
+use linalg::{self, LinearOperator};
+use ndarray_extension;
+
+/// Struct that reduces a document-term matrix (e.g. the output of
+/// TfidfVectorizer, dense or sparse) to n_components dimensions by a
+/// truncated singular value decomposition, i.e. Latent Semantic Analysis
+/// (LSA). The basic flow follows TruncatedSVD of scikit-learn with the
+/// "randomized" algorithm. The matrix is not centered, so sparse matrices
+/// stay sparse.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::lsa::TruncatedSvd;
+/// use vectorizer::tfidfvectorizer::TfidfVectorizer;
+///
+/// let docs = vec![
+///     "the cat sat on the mat",
+///     "the cat chased the dog",
+///     "stocks fell as markets closed",
+///     "markets rallied and stocks rose",
+/// ];
+///
+/// let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+/// let x = vectorizer.fit_transform(docs);
+///
+/// let mut lsa = TruncatedSvd::new(2);
+/// let x_reduced = lsa.fit_transform(&x);
+/// assert_eq!((4, 2), x_reduced.dim());
+///
+/// // Terms with the largest loadings of each component
+/// let top_terms = lsa.top_terms(&vectorizer.get_feature_names(), 3);
+/// println!("Top Terms:\n{:?}", top_terms);
+/// ```
+///
+pub struct TruncatedSvd {
+    /// Number of dimensions (singular vectors) to keep.
+    pub n_components: usize,
+
+    /// Number of random directions sampled in addition to n_components,
+    /// which improves the accuracy of the randomized SVD. Default is 10.
+    pub n_oversamples: usize,
+
+    /// Number of power iterations of the randomized SVD. More iterations
+    /// improve the accuracy when singular values decay slowly (as for text).
+    /// Default is 5.
+    pub n_iter: usize,
+
+    /// Seed of the random directions. Default is 0.
+    pub seed: u64,
+
+    /// Right singular vectors (n_components × number of columns), i.e. the
+    /// loading of each term on each component. Computed by fit().
+    pub components_: Array2<f64>,
+
+    /// Singular value of each component in descending order.
+    pub singular_values_: Array1<f64>,
+
+    /// Variance of the training documents along each component.
+    pub explained_variance_: Array1<f64>,
+
+    /// Fraction of the total variance of the training matrix explained by
+    /// each component.
+    pub explained_variance_ratio_: Array1<f64>,
+}
+
+impl TruncatedSvd {
+
+    /// Create a new instance of TruncatedSvd with n_oversamples=10, n_iter=5
+    /// and seed=0. Options can be changed via the public fields.
+    ///
+    pub fn new(n_components: usize) -> TruncatedSvd {
+        TruncatedSvd {
+            n_components,
+            n_oversamples: 10,
+            n_iter: 5,
+            seed: 0,
+            components_: Array2::<f64>::zeros((0, 0)),
+            singular_values_: Array1::<f64>::zeros(0),
+            explained_variance_: Array1::<f64>::zeros(0),
+            explained_variance_ratio_: Array1::<f64>::zeros(0),
+        }
+    }
+
+    // Store the decomposition and return the reduced matrix u · diag(s)
+    fn _fit<M: LinearOperator>(&mut self, x: &M) -> Array2<f64> {
+        let (num_rows, _) = x.shape();
+        let (u, s, vt) = linalg::randomized_svd(
+            x, self.n_components, self.n_oversamples, self.n_iter, self.seed);
+        let num_components = s.len();
+        let x_reduced = u * s.view().into_shape((1, num_components)).unwrap();
+
+        // Variance of the reduced columns relative to the total variance
+        // of the columns of x
+        let n = num_rows as f64;
+        let means = x_reduced.sum_axis(Axis(0)) / n;
+        self.explained_variance_ = x_reduced.mapv(|e| e * e).sum_axis(Axis(0)) / n
+            - means.mapv(|e| e * e);
+        let ones = Array2::<f64>::ones((num_rows, 1));
+        let column_means = x.transpose_dot(ones.view()) / n;
+        let total_variance = x.sum_of_squares() / n
+            - column_means.iter().map(|e| e * e).sum::<f64>();
+        self.explained_variance_ratio_ = if total_variance > 0. {
+            &self.explained_variance_ / total_variance
+        } else {
+            Array1::<f64>::zeros(num_components)
+        };
+
+        self.components_ = vt;
+        self.singular_values_ = s;
+        x_reduced
+    }
+
+    /// Compute the truncated SVD of a document-term matrix (Array2<f64> or
+    /// CsrMatrix<f64>). Panics if n_components is larger than the number of
+    /// rows or columns.
+    ///
+    pub fn fit<M: LinearOperator>(&mut self, x: &M) {
+        self._fit(x);
+    }
+
+    /// Project documents (rows with the columns used by fit()) onto the
+    /// components, i.e. x · components_^T. Use this for new documents
+    /// transformed by the same vectorizer.
+    ///
+    pub fn transform<M: LinearOperator>(&self, x: &M) -> Array2<f64> {
+        x.dot(self.components_.t())
+    }
+
+    /// Fit to a document-term matrix, then transform it. See fit() and
+    /// transform().
+    ///
+    pub fn fit_transform<M: LinearOperator>(&mut self, x: &M) -> Array2<f64> {
+        self._fit(x)
+    }
+
+    /// Terms with the k largest loadings of each component as (term,
+    /// loading) pairs, in descending order of loading. feature_names are the
+    /// column names, e.g. from TfidfVectorizer::get_feature_names().
+    ///
+    pub fn top_terms(&self, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>> {
+        ndarray_extension::top_terms(&self.components_, feature_names, k)
+    }
+}
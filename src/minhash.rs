@@ -0,0 +1,310 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::vec::Vec;
+use ndarray::{Array2, ArrayView1};
+use hashingvectorizer::murmurhash3_32;
+use random::Rng;
+use tokenizer::Tokenizer;
+
+// Mersenne prime 2^61 - 1 of the universal hash permutations
+const MERSENNE_PRIME: u64 = (1 << 61) - 1;
+
+/// Estimated Jaccard similarity of the shingle sets of two documents: the
+/// fraction of equal values in their MinHash signatures.
+///
+pub fn jaccard(signature_a: ArrayView1<u32>, signature_b: ArrayView1<u32>) -> f64 {
+    assert_eq!(signature_a.len(), signature_b.len(), "signatures must have the same length");
+    if signature_a.is_empty() {
+        return 0.;
+    }
+    let num_equal = signature_a.iter()
+        .zip(signature_b.iter())
+        .filter(|(a, b)| a == b)
+        .count();
+    num_equal as f64 / signature_a.len() as f64
+}
+
+/// Computes MinHash signatures of documents (i.e. Vec of string slices)
+/// from their set of shingles, the tokens of a Tokenizer (n-grams of words
+/// or of characters). The fraction of equal values in the signatures of two
+/// documents estimates the Jaccard similarity of their shingle sets (see
+/// jaccard()), and the signatures can be indexed by MinHashLsh to find
+/// near-duplicates without comparing all pairs.
+///
+/// Each shingle is hashed by murmurhash3_32() of its UTF-8 bytes, and each
+/// of the num_perm permutations is a random universal hash
+/// (a * h + b) mod (2^61 - 1) truncated to 32 bits, as in datasketch.
+/// Signatures only depend on the options, so signatures computed by two
+/// instances with the same options can be compared.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::minhash::{self, MinHash};
+///
+/// let docs = vec![
+///     "the quick brown fox jumps over the lazy dog",
+///     "the quick brown fox jumped over the lazy dog",
+///     "an entirely different sentence about cats",
+/// ];
+///
+/// let mut minhash = MinHash::new((5, 5), "lower");
+/// minhash.analyzer = "char".to_string();
+/// let signatures = minhash.transform(docs);
+///
+/// assert_eq!((3, 128), signatures.dim());
+/// assert!(minhash::jaccard(signatures.row(0), signatures.row(1)) > 0.6);
+/// assert!(minhash::jaccard(signatures.row(0), signatures.row(2)) < 0.2);
+/// ```
+///
+pub struct MinHash {
+    /// A range of n-values for the n-grams used as shingles. See also
+    /// tokenizer::Tokenizer for details.
+    pub ngram_range: (u32, u32),
+
+    /// The case of the resulting shingles. Options are "upper" and "lower".
+    /// Other inputs will use default. See also tokenizer::Tokenizer for
+    /// details.
+    pub case: String,
+
+    /// Shingles made of n-grams of words ("word") or of characters
+    /// ("char"). Default is "word". See also tokenizer::Tokenizer for
+    /// details.
+    pub analyzer: String,
+
+    /// Number of permutations, i.e. length of the signatures. The error of
+    /// the Jaccard estimates decreases as 1 / sqrt(num_perm). Default is
+    /// 128.
+    pub num_perm: usize,
+
+    /// Seed of the permutations. Default is 1.
+    pub seed: u64,
+}
+
+impl MinHash {
+
+    /// Create a new instance of MinHash with analyzer="word", num_perm=128
+    /// and seed=1. Options can be changed via the public fields.
+    ///
+    pub fn new(ngram_range: (u32, u32), case: &str) -> MinHash {
+        MinHash {
+            ngram_range,
+            case: case.to_string(),
+            analyzer: "word".to_string(),
+            num_perm: 128,
+            seed: 1,
+        }
+    }
+
+    // Parameters (a, b) of each permutation
+    fn _permutations(&self) -> Vec<(u64, u64)> {
+        let mut rng = Rng::new(self.seed);
+        (0..self.num_perm)
+            .map(|_| {
+                let a = 1 + rng.next_u64() % (MERSENNE_PRIME - 1);
+                let b = rng.next_u64() % MERSENNE_PRIME;
+                (a, b)
+            })
+            .collect()
+    }
+
+    /// MinHash signature of each document (rows, num_perm columns). Documents
+    /// without any shingle get signatures of u32::MAX values.
+    ///
+    pub fn transform(&self, docs: Vec<&str>) -> Array2<u32> {
+        let mut tokenizer = Tokenizer::new(self.ngram_range, self.case.as_str());
+        tokenizer.analyzer = self.analyzer.clone();
+        let permutations = self._permutations();
+
+        let tokenized_docs = tokenizer.tokenize(docs);
+        let mut signatures = Array2::<u32>::from_elem((tokenized_docs.len(), self.num_perm), u32::MAX);
+        for (mut signature, doc) in signatures.outer_iter_mut().zip(tokenized_docs.iter()) {
+            let shingles: HashSet<&str> = doc.iter().map(|token| token.as_str()).collect();
+            for shingle in shingles {
+                let hash = murmurhash3_32(shingle.as_bytes(), 0) as u128;
+                for (value, &(a, b)) in signature.iter_mut().zip(permutations.iter()) {
+                    let permuted = ((a as u128 * hash + b as u128) % MERSENNE_PRIME as u128) as u32;
+                    *value = (*value).min(permuted);
+                }
+            }
+        }
+        signatures
+    }
+}
+
+
+// Integral of f over [a, b] by the midpoint rule
+fn _integrate<F: Fn(f64) -> f64>(f: F, a: f64, b: f64) -> f64 {
+    let num_steps = 100;
+    let step = (b - a) / num_steps as f64;
+    (0..num_steps).map(|i| f(a + (i as f64 + 0.5) * step)).sum::<f64>() * step
+}
+
+// Number of bands and of rows per band minimizing the sum of the false
+// positive and false negative probabilities around threshold
+fn _optimal_bands(threshold: f64, num_perm: usize) -> (usize, usize) {
+    let mut best = (1, num_perm, f64::INFINITY);
+    for bands in 1..=num_perm {
+        for rows in 1..=(num_perm / bands) {
+            let candidate = |s: f64| 1. - (1. - s.powi(rows as i32)).powi(bands as i32);
+            let false_positive = _integrate(candidate, 0., threshold);
+            let false_negative = _integrate(|s| 1. - candidate(s), threshold, 1.);
+            let error = false_positive + false_negative;
+            if error < best.2 {
+                best = (bands, rows, error);
+            }
+        }
+    }
+    (best.0, best.1)
+}
+
+/// Banded locality-sensitive hashing (LSH) index of MinHash signatures,
+/// retrieving pairs of documents likely to have a Jaccard similarity above
+/// threshold. Signatures are split into bands of rows values, and documents
+/// with an identical band are candidates. Two documents of similarity s are
+/// candidates with probability 1 - (1 - s^rows)^bands, and bands and rows
+/// are chosen to make this probability steep around threshold.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::minhash::{MinHash, MinHashLsh};
+///
+/// let docs = vec![
+///     "breaking news: the river flooded the old town overnight",
+///     "scraped article on cooking pasta with fresh tomatoes",
+///     "breaking news: the river flooded the old town overnight!",
+/// ];
+///
+/// let mut minhash = MinHash::new((3, 3), "lower");
+/// minhash.analyzer = "char".to_string();
+/// let signatures = minhash.transform(docs);
+///
+/// let mut lsh = MinHashLsh::new(0.8, minhash.num_perm);
+/// lsh.fit(&signatures);
+///
+/// let pairs = lsh.duplicate_pairs();
+/// assert_eq!(1, pairs.len());
+/// assert_eq!((0, 2), (pairs[0].0, pairs[0].1));
+/// assert_eq!(vec![0, 2], lsh.query(signatures.row(2)));
+/// ```
+///
+pub struct MinHashLsh {
+    /// Jaccard similarity above which pairs are retrieved.
+    pub threshold: f64,
+
+    /// Number of bands. Computed by new() from threshold and num_perm.
+    pub bands: usize,
+
+    /// Number of signature values per band. Computed by new() from
+    /// threshold and num_perm.
+    pub rows: usize,
+
+    /// Indexed signatures. Document IDs are their positions.
+    pub signatures_: Vec<Vec<u32>>,
+
+    // Documents of each bucket of each band
+    buckets: Vec<HashMap<Vec<u32>, Vec<usize>>>,
+}
+
+impl MinHashLsh {
+
+    /// Create a new instance of MinHashLsh for signatures of num_perm values
+    /// (see MinHash::num_perm), choosing bands and rows for threshold. Panics
+    /// if num_perm is 0.
+    ///
+    pub fn new(threshold: f64, num_perm: usize) -> MinHashLsh {
+        assert!(num_perm > 0, "num_perm must be positive");
+        let (bands, rows) = _optimal_bands(threshold.clamp(0., 1.), num_perm);
+        MinHashLsh {
+            threshold,
+            bands,
+            rows,
+            signatures_: Vec::new(),
+            buckets: vec![HashMap::new(); bands],
+        }
+    }
+
+    // Bucket key of each band of a signature
+    fn _bands(&self, signature: &ArrayView1<u32>) -> Vec<Vec<u32>> {
+        let rows = self.rows;
+        (0..self.bands)
+            .map(|index_band| signature.slice(s![index_band * rows..(index_band + 1) * rows]).to_vec())
+            .collect()
+    }
+
+    /// Index signatures (rows). Any previously indexed signatures are
+    /// discarded. See also partial_fit().
+    ///
+    pub fn fit(&mut self, signatures: &Array2<u32>) {
+        self.signatures_.clear();
+        self.buckets = vec![HashMap::new(); self.bands];
+        self.partial_fit(signatures);
+    }
+
+    /// Add signatures (rows) to the index. Their document IDs follow those
+    /// of the already indexed signatures.
+    ///
+    pub fn partial_fit(&mut self, signatures: &Array2<u32>) {
+        assert!(signatures.dim().1 >= self.bands * self.rows,
+            "signatures are shorter than bands * rows");
+        for signature in signatures.outer_iter() {
+            let doc_id = self.signatures_.len();
+            let keys = self._bands(&signature);
+            for (bucket, key) in self.buckets.iter_mut().zip(keys) {
+                bucket.entry(key).or_default().push(doc_id);
+            }
+            self.signatures_.push(signature.to_vec());
+        }
+    }
+
+    /// IDs of the indexed documents sharing a band with a signature, in
+    /// ascending order. These are candidates: their similarity to the
+    /// signature may be below threshold.
+    ///
+    pub fn query(&self, signature: ArrayView1<u32>) -> Vec<usize> {
+        let mut doc_ids: Vec<usize> = self._bands(&signature).into_iter()
+            .zip(self.buckets.iter())
+            .filter_map(|(key, bucket)| bucket.get(&key))
+            .flat_map(|ids| ids.iter().cloned())
+            .collect();
+        doc_ids.sort_unstable();
+        doc_ids.dedup();
+        doc_ids
+    }
+
+    /// Pairs (i, j) of indexed documents with i < j sharing at least one
+    /// band, in ascending order.
+    ///
+    pub fn candidate_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs: HashSet<(usize, usize)> = HashSet::new();
+        for bucket in self.buckets.iter() {
+            for ids in bucket.values() {
+                for (index, &i) in ids.iter().enumerate() {
+                    for &j in ids[index + 1..].iter() {
+                        pairs.insert((i, j));
+                    }
+                }
+            }
+        }
+        let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+        pairs.sort_unstable();
+        pairs
+    }
+
+    /// Candidate pairs whose estimated Jaccard similarity is at least
+    /// threshold, as (i, j, similarity) in ascending order of (i, j).
+    ///
+    pub fn duplicate_pairs(&self) -> Vec<(usize, usize, f64)> {
+        self.candidate_pairs().into_iter()
+            .map(|(i, j)| {
+                let signature_i = ArrayView1::from(&self.signatures_[i][..]);
+                let signature_j = ArrayView1::from(&self.signatures_[j][..]);
+                (i, j, jaccard(signature_i, signature_j))
+            })
+            .filter(|&(_, _, similarity)| similarity >= self.threshold)
+            .collect()
+    }
+}
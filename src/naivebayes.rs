@@ -0,0 +1,368 @@
+use std::vec::Vec;
+use ndarray::{Array1, Array2, Axis};
+use num::ToPrimitive;
+use ndarray_extension;
+
+// Smallest smoothing parameter, as in scikit-learn. Smaller values would
+// give ln(0) feature log probabilities.
+const ALPHA_MIN: f64 = 1e-10;
+
+// Sorted unique labels and the class index of each label
+pub(crate) fn encode_labels<L: Clone + Ord>(labels: &[L]) -> (Vec<L>, Vec<usize>) {
+    let mut classes = labels.to_vec();
+    classes.sort();
+    classes.dedup();
+    let indices = labels.iter()
+        .map(|label| classes.binary_search(label).unwrap())
+        .collect();
+    (classes, indices)
+}
+
+// Number of documents and summed features of each class
+fn _count_by_class(x: &Array2<f64>, indices: &[usize], num_classes: usize) -> (Array1<f64>, Array2<f64>) {
+    let mut class_count = Array1::<f64>::zeros(num_classes);
+    let mut feature_count = Array2::<f64>::zeros((num_classes, x.dim().1));
+    for (row, &index_class) in x.outer_iter().zip(indices.iter()) {
+        class_count[index_class] += 1.;
+        let mut class_row = feature_count.row_mut(index_class);
+        class_row += &row;
+    }
+    (class_count, feature_count)
+}
+
+// Log prior of each class, empirical or uniform
+fn _class_log_prior(class_count: &Array1<f64>, fit_prior: bool) -> Array1<f64> {
+    let num_classes = class_count.len() as f64;
+    if fit_prior {
+        let total = class_count.sum();
+        class_count.mapv(|count| (count / total).ln())
+    } else {
+        Array1::from_elem(class_count.len(), -num_classes.ln())
+    }
+}
+
+// Normalize joint log likelihoods into log probabilities per row
+fn _log_normalize(mut jll: Array2<f64>) -> Array2<f64> {
+    for mut row in jll.outer_iter_mut() {
+        let max = row.fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+        let log_sum_exp = max + row.mapv(|e| (e - max).exp()).sum().ln();
+        row.mapv_inplace(|e| e - log_sum_exp);
+    }
+    jll
+}
+
+// Class with the largest joint log likelihood for each row
+fn _argmax_classes<L: Clone>(jll: &Array2<f64>, classes: &[L]) -> Vec<L> {
+    jll.outer_iter()
+        .map(|row| {
+            let candidates = row.iter().cloned().enumerate().collect();
+            let (index_class, _) = ndarray_extension::top_k(candidates, 1)[0];
+            classes[index_class].clone()
+        })
+        .collect()
+}
+
+// Features with the k largest log probability ratios between each class and
+// the most likely of the other classes
+fn _most_informative_features(feature_log_prob: &Array2<f64>, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>> {
+    let (num_classes, num_columns) = feature_log_prob.dim();
+    (0..num_classes)
+        .map(|index_class| {
+            let candidates = (0..num_columns)
+                .map(|index_col| {
+                    let other_max = (0..num_classes)
+                        .filter(|&c| c != index_class)
+                        .map(|c| feature_log_prob[[c, index_col]])
+                        .fold(f64::NEG_INFINITY, f64::max);
+                    let log_prob = feature_log_prob[[index_class, index_col]];
+                    let ratio = if num_classes > 1 { log_prob - other_max } else { log_prob };
+                    (index_col, ratio)
+                })
+                .collect();
+            ndarray_extension::top_k(candidates, k).into_iter()
+                .map(|(index_col, ratio)| (feature_names[index_col].clone(), ratio))
+                .collect()
+        })
+        .collect()
+}
+
+fn _to_f64<T: Clone + ToPrimitive>(x: &Array2<T>) -> Array2<f64> {
+    x.mapv(|e| e.to_f64().unwrap())
+}
+
+// Shared input checks of fit(), x is already converted to f64
+//
+fn _check_fit_input<L>(x: &Array2<f64>, labels: &[L]) {
+    assert_eq!(x.dim().0, labels.len(), "x and labels must have the same length");
+    assert!(!labels.is_empty(), "x and labels must not be empty");
+    assert!(x.iter().all(|&e| e >= 0.), "x must be non-negative");
+}
+
+
+/// Naive Bayes classifier for multinomially distributed features, e.g. token
+/// counts from CountVectorizer or weights from TfidfVectorizer. The basic
+/// flow follows MultinomialNB of scikit-learn. Labels can be of any type
+/// that can be sorted (e.g. String or usize).
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::countvectorizer::CountVectorizer;
+/// use vectorizer::naivebayes::MultinomialNb;
+///
+/// let docs = vec![
+///     "great movie, loved it",
+///     "what a great story",
+///     "boring and bad movie",
+///     "bad acting, boring story",
+/// ];
+/// let labels = vec!["pos", "pos", "neg", "neg"];
+///
+/// let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+/// let x = vectorizer.fit_transform(docs);
+///
+/// let mut classifier = MultinomialNb::new();
+/// classifier.fit(&x, &labels);
+///
+/// let x_new = vectorizer.transform(vec!["a great movie", "so boring"]);
+/// assert_eq!(vec!["pos", "neg"], classifier.predict(&x_new));
+///
+/// let features = classifier.most_informative_features(&vectorizer.get_feature_names(), 2);
+/// println!("Most informative features:\n{:?}", features);
+/// ```
+///
+pub struct MultinomialNb<L> {
+    /// Additive smoothing parameter: 1.0 for Laplace smoothing, smaller
+    /// values for Lidstone smoothing. Values below 1e-10 are clamped to
+    /// 1e-10. Default is 1.0.
+    pub alpha: f64,
+
+    /// If true, class priors are learned from the labels, otherwise they are
+    /// uniform. Default is true.
+    pub fit_prior: bool,
+
+    /// Sorted unique labels seen by fit().
+    pub classes_: Vec<L>,
+
+    /// Number of training documents of each class.
+    pub class_count_: Array1<f64>,
+
+    /// Sum of each feature over the training documents of each class
+    /// (classes × features).
+    pub feature_count_: Array2<f64>,
+
+    /// Log prior probability of each class.
+    pub class_log_prior_: Array1<f64>,
+
+    /// Smoothed log probability of each feature given each class (classes ×
+    /// features).
+    pub feature_log_prob_: Array2<f64>,
+}
+
+impl<L: Clone + Ord> MultinomialNb<L> {
+
+    /// Create a new instance of MultinomialNb with alpha=1.0 and
+    /// fit_prior=true. Options can be changed via the public fields.
+    ///
+    pub fn new() -> MultinomialNb<L> {
+        MultinomialNb {
+            alpha: 1.,
+            fit_prior: true,
+            classes_: Vec::new(),
+            class_count_: Array1::<f64>::zeros(0),
+            feature_count_: Array2::<f64>::zeros((0, 0)),
+            class_log_prior_: Array1::<f64>::zeros(0),
+            feature_log_prob_: Array2::<f64>::zeros((0, 0)),
+        }
+    }
+
+    /// Learn the class priors and feature probabilities from documents (rows
+    /// of x, non-negative) and their labels. Panics if x is empty, has a
+    /// negative value or its row count differs from the number of labels.
+    ///
+    pub fn fit<T: Clone + ToPrimitive>(&mut self, x: &Array2<T>, labels: &[L]) {
+        let x = _to_f64(x);
+        _check_fit_input(&x, labels);
+        let (classes, indices) = encode_labels(labels);
+        let (class_count, feature_count) = _count_by_class(&x, &indices, classes.len());
+
+        let alpha = self.alpha.max(ALPHA_MIN);
+        let smoothed = &feature_count + alpha;
+        let totals = smoothed.sum_axis(Axis(1)).insert_axis(Axis(1));
+        self.feature_log_prob_ = (smoothed / totals).mapv(f64::ln);
+        self.class_log_prior_ = _class_log_prior(&class_count, self.fit_prior);
+        self.classes_ = classes;
+        self.class_count_ = class_count;
+        self.feature_count_ = feature_count;
+    }
+
+    // Unnormalized log posterior of each class for each row
+    fn _joint_log_likelihood<T: Clone + ToPrimitive>(&self, x: &Array2<T>) -> Array2<f64> {
+        assert!(!self.classes_.is_empty(), "MultinomialNb is not fitted, call fit() first");
+        _to_f64(x).dot(&self.feature_log_prob_.t()) + &self.class_log_prior_
+    }
+
+    /// Most probable label of each document.
+    ///
+    pub fn predict<T: Clone + ToPrimitive>(&self, x: &Array2<T>) -> Vec<L> {
+        _argmax_classes(&self._joint_log_likelihood(x), &self.classes_)
+    }
+
+    /// Log probability of each class (columns in the order of classes_) for
+    /// each document.
+    ///
+    pub fn predict_log_proba<T: Clone + ToPrimitive>(&self, x: &Array2<T>) -> Array2<f64> {
+        _log_normalize(self._joint_log_likelihood(x))
+    }
+
+    /// Probability of each class (columns in the order of classes_) for each
+    /// document.
+    ///
+    pub fn predict_proba<T: Clone + ToPrimitive>(&self, x: &Array2<T>) -> Array2<f64> {
+        self.predict_log_proba(x).mapv(f64::exp)
+    }
+
+    /// For each class (in the order of classes_), the k features whose log
+    /// probability exceeds that of the most likely other class by the most,
+    /// as (feature name, log probability ratio) pairs. feature_names are the
+    /// column names, e.g. from CountVectorizer::get_feature_names().
+    ///
+    pub fn most_informative_features(&self, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>> {
+        _most_informative_features(&self.feature_log_prob_, feature_names, k)
+    }
+}
+
+impl<L: Clone + Ord> Default for MultinomialNb<L> {
+    fn default() -> MultinomialNb<L> {
+        MultinomialNb::new()
+    }
+}
+
+
+/// Naive Bayes classifier for binary features, i.e. whether each token
+/// occurs in a document or not. Features are binarized with binarize, and
+/// the absence of a token counts as evidence too. The basic flow follows
+/// BernoulliNB of scikit-learn. See also MultinomialNb.
+///
+pub struct BernoulliNb<L> {
+    /// Additive smoothing parameter: 1.0 for Laplace smoothing, smaller
+    /// values for Lidstone smoothing. Values below 1e-10 are clamped to
+    /// 1e-10. Default is 1.0.
+    pub alpha: f64,
+
+    /// If true, class priors are learned from the labels, otherwise they are
+    /// uniform. Default is true.
+    pub fit_prior: bool,
+
+    /// Threshold above which a feature is considered present. If None, the
+    /// features are assumed to be binary already. Default is Some(0.0).
+    pub binarize: Option<f64>,
+
+    /// Sorted unique labels seen by fit().
+    pub classes_: Vec<L>,
+
+    /// Number of training documents of each class.
+    pub class_count_: Array1<f64>,
+
+    /// Number of training documents of each class containing each feature
+    /// (classes × features).
+    pub feature_count_: Array2<f64>,
+
+    /// Log prior probability of each class.
+    pub class_log_prior_: Array1<f64>,
+
+    /// Smoothed log probability that each feature is present given each
+    /// class (classes × features).
+    pub feature_log_prob_: Array2<f64>,
+}
+
+impl<L: Clone + Ord> BernoulliNb<L> {
+
+    /// Create a new instance of BernoulliNb with alpha=1.0, fit_prior=true
+    /// and binarize=Some(0.0). Options can be changed via the public fields.
+    ///
+    pub fn new() -> BernoulliNb<L> {
+        BernoulliNb {
+            alpha: 1.,
+            fit_prior: true,
+            binarize: Some(0.),
+            classes_: Vec::new(),
+            class_count_: Array1::<f64>::zeros(0),
+            feature_count_: Array2::<f64>::zeros((0, 0)),
+            class_log_prior_: Array1::<f64>::zeros(0),
+            feature_log_prob_: Array2::<f64>::zeros((0, 0)),
+        }
+    }
+
+    fn _binarize<T: Clone + ToPrimitive>(&self, x: &Array2<T>) -> Array2<f64> {
+        let x = _to_f64(x);
+        match self.binarize {
+            Some(threshold) => x.mapv(|e| if e > threshold { 1. } else { 0. }),
+            None => x,
+        }
+    }
+
+    /// Learn the class priors and feature probabilities from documents (rows
+    /// of x) and their labels. Panics if x is empty, has a negative value
+    /// (after binarization) or its row count differs from the number of labels.
+    ///
+    pub fn fit<T: Clone + ToPrimitive>(&mut self, x: &Array2<T>, labels: &[L]) {
+        let x = self._binarize(x);
+        _check_fit_input(&x, labels);
+        let (classes, indices) = encode_labels(labels);
+        let (class_count, feature_count) = _count_by_class(&x, &indices, classes.len());
+
+        let alpha = self.alpha.max(ALPHA_MIN);
+        let smoothed = &feature_count + alpha;
+        let totals = (&class_count + 2. * alpha).insert_axis(Axis(1));
+        self.feature_log_prob_ = (smoothed / totals).mapv(f64::ln);
+        self.class_log_prior_ = _class_log_prior(&class_count, self.fit_prior);
+        self.classes_ = classes;
+        self.class_count_ = class_count;
+        self.feature_count_ = feature_count;
+    }
+
+    // Unnormalized log posterior of each class for each row
+    fn _joint_log_likelihood<T: Clone + ToPrimitive>(&self, x: &Array2<T>) -> Array2<f64> {
+        assert!(!self.classes_.is_empty(), "BernoulliNb is not fitted, call fit() first");
+        let x = self._binarize(x);
+        let neg_prob = self.feature_log_prob_.mapv(|e| (-e.exp()).ln_1p());
+        let neg_prob_sums = neg_prob.sum_axis(Axis(1));
+        x.dot(&(&self.feature_log_prob_ - &neg_prob).t()) + &self.class_log_prior_ + &neg_prob_sums
+    }
+
+    /// Most probable label of each document.
+    ///
+    pub fn predict<T: Clone + ToPrimitive>(&self, x: &Array2<T>) -> Vec<L> {
+        _argmax_classes(&self._joint_log_likelihood(x), &self.classes_)
+    }
+
+    /// Log probability of each class (columns in the order of classes_) for
+    /// each document.
+    ///
+    pub fn predict_log_proba<T: Clone + ToPrimitive>(&self, x: &Array2<T>) -> Array2<f64> {
+        _log_normalize(self._joint_log_likelihood(x))
+    }
+
+    /// Probability of each class (columns in the order of classes_) for each
+    /// document.
+    ///
+    pub fn predict_proba<T: Clone + ToPrimitive>(&self, x: &Array2<T>) -> Array2<f64> {
+        self.predict_log_proba(x).mapv(f64::exp)
+    }
+
+    /// For each class (in the order of classes_), the k features whose
+    /// presence is the most indicative of the class. See
+    /// MultinomialNb::most_informative_features().
+    ///
+    pub fn most_informative_features(&self, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>> {
+        _most_informative_features(&self.feature_log_prob_, feature_names, k)
+    }
+}
+
+impl<L: Clone + Ord> Default for BernoulliNb<L> {
+    fn default() -> BernoulliNb<L> {
+        BernoulliNb::new()
+    }
+}
@@ -1,7 +1,9 @@
 // use ndarray;
-use ndarray::{Array1, Array2, Axis};
-use num::{NumCast, Zero, ToPrimitive}; //{PrimInt, Unsigned, BigInt, BigRational};
+use ndarray::{Array1, Array2, ArrayBase, ArrayView2, Axis, Data, DataMut, Ix2, Zip};
+use num::{Float, NumCast, Zero, ToPrimitive}; //{PrimInt, Unsigned, BigInt, BigRational};
 use std::clone::Clone;
+use std::cmp::Ordering;
+use sparse::CsrMatrix;
 
 
 /// Convert an Array2<T> into an Array2<f64> of the content.
@@ -30,25 +32,117 @@ use std::clone::Clone;
 /// ```
 /// 
 pub fn convert_matrix_to_f64<T: Clone + ToPrimitive>(array: Array2<T>) -> Array2<f64> {
-        let array_f64 = array.mapv(|e| e.to_f64().unwrap());
-        array_f64
+        array.mapv(|e| e.to_f64().unwrap())
 }
 
-/// Count number of non-zero rows for each column. 
-/// *** The non-zero columns for each row to be implemented
-pub fn bincount(matrix: Array2<u64>)  -> Array1<f64> {
-    let (num_rows, num_columns) = matrix.dim();
-    let mut bincounts = Array1::<f64>::zeros(num_columns);
-    for index_row in 0..num_rows {
-        for index_col in 0..num_columns {
-            if matrix[[index_row, index_col]] != 0 {
-                bincounts[index_col] += 1.;
+/// Convert an Array2<T> into an Array2<U> of the content, where U is any
+/// primitive numeric type. Returns None if an element cannot be represented
+/// by U (e.g. 300 as u8, or a negative value as an unsigned type).
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::arr2;
+/// use vectorizer::ndarray_extension;
+///
+/// let x = arr2(&[
+///     [1u64, 2, 3],
+///     [255, 0, 7]]);
+/// let y = ndarray_extension::convert_matrix::<u64, u8>(x.clone()).unwrap();
+/// assert_eq!(x.mapv(|e| e as u8), y);
+///
+/// let z = ndarray_extension::convert_matrix::<u64, u8>(x + 1);
+/// assert!(z.is_none());
+/// ```
+///
+pub fn convert_matrix<T: Clone + ToPrimitive, U: NumCast>(array: Array2<T>) -> Option<Array2<U>> {
+    let dim = array.dim();
+    let mut elements: Vec<U> = Vec::with_capacity(array.len());
+    for e in array.iter() {
+        elements.push(U::from(e.clone())?);
+    }
+    Some(Array2::from_shape_vec(dim, elements).unwrap())
+}
+
+/// Count the non-zero elements of a 2D array (Array2<T> or a view of it)
+/// along an axis. With Axis(0), the number of non-zero rows is counted for
+/// each column (e.g. document frequencies of a count matrix). With Axis(1),
+/// the number of non-zero columns is counted for each row (e.g. the number
+/// of unique terms of each document). Panics if axis is not Axis(0) or Axis(1).
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::{arr1, arr2, Axis};
+/// use vectorizer::ndarray_extension;
+///
+/// let x = arr2(&[
+///     [1, 0, 0],
+///     [3, 2, 0],
+///     [1, 0, 0]]);
+///
+/// assert_eq!(arr1(&[3, 1, 0]), ndarray_extension::bincount(&x, Axis(0)));
+/// assert_eq!(arr1(&[1, 2, 1]), ndarray_extension::bincount(&x.view(), Axis(1)));
+/// ```
+///
+pub fn bincount<S, T>(matrix: &ArrayBase<S, Ix2>, axis: Axis) -> Array1<u64>
+    where S: Data<Elem = T>, T: Zero
+{
+    assert!(axis.index() < 2, "axis must be Axis(0) or Axis(1)");
+    let counts_len = matrix.len_of(Axis(1 - axis.index()));
+    let mut bincounts = Array1::<u64>::zeros(counts_len);
+    for lane in matrix.axis_iter(axis) {
+        Zip::from(&mut bincounts).and(&lane).apply(|count, e| {
+            if !e.is_zero() {
+                *count += 1;
             }
-        }
+        });
     }
     bincounts
 }
 
+/// Sparse version of bincount(). Explicitly stored zeros are not counted.
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::{arr1, arr2, Axis};
+/// use vectorizer::ndarray_extension;
+/// use vectorizer::sparse::CsrMatrix;
+///
+/// let x = CsrMatrix::from_dense(&arr2(&[
+///     [1, 0, 0],
+///     [3, 2, 0]]));
+///
+/// assert_eq!(arr1(&[2, 1, 0]), ndarray_extension::sparse_bincount(&x, Axis(0)));
+/// assert_eq!(arr1(&[1, 2]), ndarray_extension::sparse_bincount(&x, Axis(1)));
+/// ```
+///
+pub fn sparse_bincount<T: Zero>(matrix: &CsrMatrix<T>, axis: Axis) -> Array1<u64> {
+    let (num_rows, num_columns) = matrix.dim();
+    match axis.index() {
+        0 => {
+            let mut bincounts = Array1::<u64>::zeros(num_columns);
+            for (index_col, e) in matrix.indices.iter().zip(matrix.data.iter()) {
+                if !e.is_zero() {
+                    bincounts[*index_col] += 1;
+                }
+            }
+            bincounts
+        },
+        1 => Array1::from_shape_fn(num_rows, |index_row| {
+            matrix.row(index_row).1.iter().filter(|e| !e.is_zero()).count() as u64
+        }),
+        _ => panic!("axis must be Axis(0) or Axis(1)"),
+    }
+}
+
 /// Convert an Array1<T> into diagonal Array2<f64>.
 /// It binds the Type T with Clone + ToPrimitive
 /// 
@@ -92,19 +186,102 @@ pub fn vec2diagonal<T: Clone + Zero + ToPrimitive>(vector: Array1<T>) -> Array2<
     matrix.mapv(|e| e.to_f64().unwrap())
 }
 
+// Norm of a sequence of values. Options are "l1", "l2" and "max".
+fn _norm<I: Iterator<Item = f64>>(values: I, norm: &str) -> f64 {
+    match norm {
+        "l1" => values.map(f64::abs).sum(),
+        "l2" => values.map(|e| e * e).sum::<f64>().sqrt(),
+        "max" => values.map(f64::abs).fold(0., f64::max),
+        _ => panic!("unknown norm: {} (options are \"l1\", \"l2\" and \"max\")", norm),
+    }
+}
+
+/// Calculates the norm of each lane of a 2D array (Array2<T> or a view of
+/// it) along an axis, i.e. of each row with Axis(1) and of each column with
+/// Axis(0). Options of norm are "l1" (sum of absolute values), "l2" (square
+/// root of the sum of squares) and "max" (largest absolute value). Panics on
+/// any other norm.
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::{arr1, arr2, Axis};
+/// use vectorizer::ndarray_extension;
+///
+/// let x = arr2(&[
+///     [3, 0, -4],
+///     [0, 0, 0]]);
+///
+/// assert_eq!(arr1(&[5., 0.]), ndarray_extension::norms(&x, "l2", Axis(1)));
+/// assert_eq!(arr1(&[3., 0., 4.]), ndarray_extension::norms(&x, "max", Axis(0)));
+/// ```
+///
+pub fn norms<S, T>(x: &ArrayBase<S, Ix2>, norm: &str, axis: Axis) -> Array1<f64>
+    where S: Data<Elem = T>, T: ToPrimitive
+{
+    let lanes = x.lanes(axis);
+    let norms: Vec<f64> = lanes.into_iter()
+        .map(|lane| _norm(lane.iter().map(|e| e.to_f64().unwrap()), norm))
+        .collect();
+    Array1::from(norms)
+}
+
+/// Normalize each lane of a 2D array in place (Array2<T> or a mutable view
+/// of it), i.e. each row with Axis(1) and each column with Axis(0). See
+/// norms() for the options of norm. Lanes whose norm is not larger than
+/// epsilon (e.g. rows of documents without any known token) are left
+/// unchanged instead of being divided by (almost) zero.
+///
+/// # Examples
+/// ```
+/// #[macro_use]
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::{arr2, Axis};
+/// use vectorizer::ndarray_extension;
+///
+/// # fn main() {
+/// let mut x = arr2(&[
+///     [3.0, 0.0, 4.0],
+///     [0.0, 0.0, 0.0],
+///     [1.0, 2.0, 1.0]]);
+///
+/// // Normalize the first two rows only
+/// ndarray_extension::normalize_inplace(&mut x.slice_mut(s![..2, ..]), "l2", Axis(1), 0.);
+///
+/// assert_eq!(arr2(&[
+///     [0.6, 0.0, 0.8],
+///     [0.0, 0.0, 0.0],
+///     [1.0, 2.0, 1.0]]), x);
+/// # }
+/// ```
+///
+pub fn normalize_inplace<S, T>(x: &mut ArrayBase<S, Ix2>, norm: &str, axis: Axis, epsilon: T)
+    where S: DataMut<Elem = T>, T: Float
+{
+    for mut lane in x.lanes_mut(axis) {
+        let lane_norm = _norm(lane.iter().map(|e| e.to_f64().unwrap()), norm);
+        let lane_norm = T::from(lane_norm).unwrap();
+        if lane_norm > epsilon {
+            lane.mapv_inplace(|e| e / lane_norm);
+        }
+    }
+}
+
 /// Calculates L2 norm for each row of an Array2<T>. An L2 norm is
 /// calculated as sqrt(n1^2, n2^2, ..., nN^2), where ni denotes i-th element
-/// in a row. The outpu will be an Array2<f64>
+/// in a row. The outpu will be an Array2<f64>. See also norms().
 /// 
 pub fn row_l2_norms<T: NumCast + Clone>(x: Array2<T>) -> Array1<f64> {
-    let mut x_f64 = convert_matrix_to_f64(x);
-    x_f64 = x_f64.mapv(|e| e.powi(2));
-    let squared_sums = x_f64.sum_axis(Axis(1));
-    squared_sums.mapv(f64::sqrt)
+    norms(&x, "l2", Axis(1))
 }
 
 /// L1 normalize an Array2<T>. Each individual row is normalized. L1 norm
-/// is the sum of the absolute values of all the elements in a row.
+/// is the sum of the absolute values of all the elements in a row. Rows of
+/// zeros stay zero. See also normalize_inplace().
 /// 
 /// # Examples
 /// ```
@@ -133,15 +310,13 @@ pub fn row_l2_norms<T: NumCast + Clone>(x: Array2<T>) -> Array1<f64> {
 /// 
 pub fn l1_normalize<T: NumCast + Clone>(x: Array2<T>) -> Array2<f64> {
     let mut x_f64 = convert_matrix_to_f64(x);
-    x_f64 = x_f64.mapv(f64::abs);
-    let norms = x_f64.sum_axis(Axis(1));
-    let num_rows = norms.len();
-    let row_norms = norms.into_shape((num_rows, 1)).unwrap();
-    x_f64 / row_norms
+    normalize_inplace(&mut x_f64, "l1", Axis(1), 0.);
+    x_f64
 }
 
 /// L2 normalize an Array2<T>. Each individual row is normalized. L2 norms
-/// are calculated using row_l2_norms().
+/// are calculated using row_l2_norms(). Rows of zeros stay zero. See also
+/// normalize_inplace().
 /// 
 /// # Examples
 /// ```
@@ -178,9 +353,311 @@ pub fn l1_normalize<T: NumCast + Clone>(x: Array2<T>) -> Array2<f64> {
 /// ```
 /// 
 pub fn l2_normalize<T: NumCast + Clone>(x: Array2<T>) -> Array2<f64> {
-    let x_f64 = convert_matrix_to_f64(x);
-    let norms = row_l2_norms(x_f64.clone());
-    let num_rows = norms.len();
-    let row_norms = norms.into_shape((num_rows, 1)).unwrap();
-    x_f64 / row_norms
+    let mut x_f64 = convert_matrix_to_f64(x);
+    normalize_inplace(&mut x_f64, "l2", Axis(1), 0.);
+    x_f64
+}
+
+
+/// Pivoted unique normalize an Array2<T> (Singhal et al., 1996). Each row is
+/// divided by (1 - slope) * pivot + slope * u, where u is the number of
+/// non-zero (i.e. unique term) elements in that row. Rows with as many unique
+/// terms as the pivot are normalized as with "pivot" itself, and longer rows
+/// are penalized less than with cosine normalization. Rows whose divisor is
+/// not positive (e.g. empty rows with a slope of 1.0 or a pivot of 0) are
+/// left unchanged, as in normalize_inplace().
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::arr2;
+/// use vectorizer::ndarray_extension;
+///
+/// let x = arr2(&[
+///     [1.0, 1.0, 0.0, 0.0],
+///     [1.0, 1.0, 1.0, 1.0]]);
+///
+/// // pivot = 2 unique terms, slope = 0.5
+/// let ans_x = arr2(&[
+///     [0.5, 0.5, 0.0, 0.0],
+///     [1.0/3., 1.0/3., 1.0/3., 1.0/3.]]);
+///
+/// let pivoted = ndarray_extension::pivoted_unique_normalize(x, 2.0, 0.5);
+/// assert_eq!(ans_x, pivoted);
+/// ```
+///
+pub fn pivoted_unique_normalize<T: Float>(mut x: Array2<T>, pivot: f64, slope: f64) -> Array2<T> {
+    let unique_counts = bincount(&x, Axis(1));
+    for (mut row, &u) in x.outer_iter_mut().zip(unique_counts.iter()) {
+        let row_norm = T::from((1. - slope) * pivot + slope * u as f64).unwrap();
+        if row_norm > T::zero() {
+            row.mapv_inplace(|e| e / row_norm);
+        }
+    }
+    x
+}
+
+// Total order of values, NaN (the values not comparable with themselves)
+// being the smallest. Used by top_k() and
+// countvectorizer::inverse_transform_with_weights() so that both rank NaN
+// last.
+//
+pub(crate) fn cmp_nan_last<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    match (a.partial_cmp(a).is_none(), b.partial_cmp(b).is_none()) {
+        (true, true) => Ordering::Equal,
+        (true, false) => Ordering::Less,
+        (false, true) => Ordering::Greater,
+        (false, false) => a.partial_cmp(b).unwrap(),
+    }
+}
+
+/// Select the k (index, value) pairs with the largest values, sorted by value
+/// in descending order (ties in ascending order of index). Uses partial
+/// selection, so only the selected k pairs are sorted. NaN values rank below
+/// all other values, so they are only selected when fewer than k other pairs
+/// are left.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::ndarray_extension;
+///
+/// let candidates = vec![(0, 0.1), (1, 0.7), (2, 0.3), (3, 0.7), (4, 0.2)];
+/// let top = ndarray_extension::top_k(candidates, 3);
+/// assert_eq!(vec![(1, 0.7), (3, 0.7), (2, 0.3)], top);
+/// ```
+///
+pub fn top_k<T: Float>(mut candidates: Vec<(usize, T)>, k: usize) -> Vec<(usize, T)> {
+    let compare = |a: &(usize, T), b: &(usize, T)| {
+        cmp_nan_last(&b.1, &a.1).then(a.0.cmp(&b.0))
+    };
+    if k == 0 {
+        return Vec::new();
+    }
+    if candidates.len() > k {
+        candidates.select_nth_unstable_by(k - 1, compare);
+        candidates.truncate(k);
+    }
+    candidates.sort_unstable_by(compare);
+    candidates
+}
+
+// Names of the columns with the k largest values of each row of a matrix
+// (e.g. the components of a topic model), as (name, value) pairs in
+// descending order of value. See top_k().
+//
+pub(crate) fn top_terms<S>(rows: &ArrayBase<S, Ix2>, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>>
+    where S: Data<Elem = f64>
+{
+    rows.outer_iter()
+        .map(|row| {
+            let weights = row.iter().cloned().enumerate().collect();
+            top_k(weights, k).into_iter()
+                .map(|(index_col, weight)| (feature_names[index_col].clone(), weight))
+                .collect()
+        })
+        .collect()
+}
+
+
+// Similarity (or distance) computed by the pairwise functions
+#[derive(Clone, Copy)]
+enum Metric {
+    Dot,
+    Cosine,
+    Euclidean,
+}
+
+impl Metric {
+    fn parse(metric: &str) -> Metric {
+        match metric {
+            "dot" => Metric::Dot,
+            "cosine" => Metric::Cosine,
+            "euclidean" => Metric::Euclidean,
+            _ => panic!("unknown metric {:?}. Options are \"cosine\", \"dot\" and \"euclidean\"", metric),
+        }
+    }
+
+    // Metric value from the dot product and the l2 norms of the two rows.
+    // The cosine similarity with a zero row is 0.
+    fn value_from_dot(&self, dot: f64, norm_x: f64, norm_y: f64) -> f64 {
+        match self {
+            Metric::Dot => dot,
+            Metric::Cosine if norm_x == 0. || norm_y == 0. => 0.,
+            Metric::Cosine => dot / (norm_x * norm_y),
+            Metric::Euclidean => (norm_x * norm_x + norm_y * norm_y - 2. * dot).max(0.).sqrt(),
+        }
+    }
+
+    // Key to select nearest neighbors by: the largest similarity or the
+    // smallest distance.
+    fn ranking_key(&self, value: f64) -> f64 {
+        match self {
+            Metric::Euclidean => -value,
+            _ => value,
+        }
+    }
+}
+
+// Metric values between a block of rows of x and all the rows of y
+fn _pairwise_block(x_block: ArrayView2<f64>, y: &Array2<f64>, y_norms: &Array1<f64>, metric: Metric) -> Array2<f64> {
+    let x_norms = x_block.map_axis(Axis(1), |row| row.dot(&row).sqrt());
+    let mut block = x_block.dot(&y.t());
+    for ((index_x, index_y), e) in block.indexed_iter_mut() {
+        *e = metric.value_from_dot(*e, x_norms[index_x], y_norms[index_y]);
+    }
+    block
+}
+
+/// Pairwise similarity (or distance) between each row of x and each row of
+/// y. The element [[i, j]] of the result compares the i-th row of x with the
+/// j-th row of y. Options of metric are "cosine" (cosine similarity, 0 for
+/// zero rows), "dot" (dot product, the same as cosine for L2 normalized rows
+/// such as Tf-Idf output) and "euclidean" (Euclidean distance).
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::arr2;
+/// use vectorizer::ndarray_extension;
+///
+/// let x = arr2(&[
+///     [1.0, 0.0],
+///     [3.0, 4.0]]);
+/// let y = arr2(&[
+///     [0.0, 2.0]]);
+///
+/// let cosine = ndarray_extension::pairwise_similarity(&x, &y, "cosine");
+/// let dot = ndarray_extension::pairwise_similarity(&x, &y, "dot");
+/// let euclidean = ndarray_extension::pairwise_similarity(&x, &y, "euclidean");
+/// assert_eq!(arr2(&[[0.0], [0.8]]), cosine);
+/// assert_eq!(arr2(&[[0.0], [8.0]]), dot);
+/// assert_eq!(arr2(&[[f64::sqrt(5.)], [f64::sqrt(13.)]]), euclidean);
+/// ```
+///
+pub fn pairwise_similarity(x: &Array2<f64>, y: &Array2<f64>, metric: &str) -> Array2<f64> {
+    let metric = Metric::parse(metric);
+    let y_norms = y.map_axis(Axis(1), |row| row.dot(&row).sqrt());
+    _pairwise_block(x.view(), y, &y_norms, metric)
+}
+
+/// Top-k nearest rows of y for each row of x, as (row index of y, value)
+/// pairs sorted from the nearest. See pairwise_similarity() for the metric
+/// options; "cosine" and "dot" rank by the largest similarity and
+/// "euclidean" by the smallest distance. Rows of x are processed in blocks
+/// of block_size rows, so that at most block_size x (rows of y) values are
+/// held in memory at a time. If x and y are the same matrix, each row is
+/// its own nearest neighbor.
+///
+/// # Examples
+/// ```
+/// extern crate ndarray;
+/// extern crate vectorizer;
+///
+/// use ndarray::arr2;
+/// use vectorizer::ndarray_extension;
+///
+/// let x = arr2(&[
+///     [1.0, 0.1]]);
+/// let y = arr2(&[
+///     [0.0, 1.0],
+///     [1.0, 0.0],
+///     [1.0, 1.0]]);
+///
+/// let neighbors = ndarray_extension::nearest_neighbors(&x, &y, 2, "cosine", 1024);
+/// assert_eq!(vec![1, 2], neighbors[0].iter().map(|&(j, _)| j).collect::<Vec<usize>>());
+/// ```
+///
+pub fn nearest_neighbors(x: &Array2<f64>, y: &Array2<f64>, k: usize, metric: &str, block_size: usize) -> Vec<Vec<(usize, f64)>> {
+    assert!(block_size > 0, "block_size must be positive");
+    let metric = Metric::parse(metric);
+    let y_norms = y.map_axis(Axis(1), |row| row.dot(&row).sqrt());
+
+    let mut neighbors: Vec<Vec<(usize, f64)>> = Vec::with_capacity(x.nrows());
+    for x_block in x.axis_chunks_iter(Axis(0), block_size) {
+        let block = _pairwise_block(x_block, y, &y_norms, metric);
+        for row in block.outer_iter() {
+            let candidates = row.iter()
+                .enumerate()
+                .map(|(index_y, e)| (index_y, metric.ranking_key(*e)))
+                .collect();
+            neighbors.push(top_k(candidates, k).into_iter()
+                .map(|(index_y, key)| (index_y, metric.ranking_key(key)))
+                .collect());
+        }
+    }
+    neighbors
+}
+
+// Metric values between the index_x-th row of x and all the rows of y,
+// where y_t is the transpose of y.
+fn _sparse_pairwise_row(x: &CsrMatrix<f64>, index_x: usize, y_t: &CsrMatrix<f64>, y_norms: &[f64], metric: Metric) -> Vec<f64> {
+    let (row_indices, row_data) = x.row(index_x);
+    let norm_x = row_data.iter().map(|e| e * e).sum::<f64>().sqrt();
+
+    // Accumulate dot products over the columns shared with each row of y
+    let mut dots = vec![0.; y_t.shape.1];
+    for (index_col, value) in row_indices.iter().zip(row_data.iter()) {
+        let (y_indices, y_data) = y_t.row(*index_col);
+        for (index_y, y_value) in y_indices.iter().zip(y_data.iter()) {
+            dots[*index_y] += value * y_value;
+        }
+    }
+    dots.iter()
+        .zip(y_norms.iter())
+        .map(|(dot, norm_y)| metric.value_from_dot(*dot, norm_x, *norm_y))
+        .collect()
+}
+
+fn _sparse_row_norms(x: &CsrMatrix<f64>) -> Vec<f64> {
+    (0..x.shape.0)
+        .map(|index_row| x.row(index_row).1.iter().map(|e| e * e).sum::<f64>().sqrt())
+        .collect()
+}
+
+/// Sparse version of pairwise_similarity(). The dot products only visit the
+/// columns shared by each pair of rows.
+///
+pub fn sparse_pairwise_similarity(x: &CsrMatrix<f64>, y: &CsrMatrix<f64>, metric: &str) -> Array2<f64> {
+    assert_eq!(x.shape.1, y.shape.1, "x and y must have the same number of columns");
+    let metric = Metric::parse(metric);
+    let y_t = y.transpose();
+    let y_norms = _sparse_row_norms(y);
+
+    let mut similarity = Array2::<f64>::zeros((x.shape.0, y.shape.0));
+    for index_x in 0..x.shape.0 {
+        let row = _sparse_pairwise_row(x, index_x, &y_t, &y_norms, metric);
+        for (index_y, value) in row.into_iter().enumerate() {
+            similarity[[index_x, index_y]] = value;
+        }
+    }
+    similarity
+}
+
+/// Sparse version of nearest_neighbors(). Rows of x are processed one at a
+/// time, so only (rows of y) values are held in memory at a time.
+///
+pub fn sparse_nearest_neighbors(x: &CsrMatrix<f64>, y: &CsrMatrix<f64>, k: usize, metric: &str) -> Vec<Vec<(usize, f64)>> {
+    assert_eq!(x.shape.1, y.shape.1, "x and y must have the same number of columns");
+    let metric = Metric::parse(metric);
+    let y_t = y.transpose();
+    let y_norms = _sparse_row_norms(y);
+
+    (0..x.shape.0)
+        .map(|index_x| {
+            let candidates = _sparse_pairwise_row(x, index_x, &y_t, &y_norms, metric)
+                .into_iter()
+                .map(|e| metric.ranking_key(e))
+                .enumerate()
+                .collect();
+            top_k(candidates, k).into_iter()
+                .map(|(index_y, key)| (index_y, metric.ranking_key(key)))
+                .collect()
+        })
+        .collect()
 }
@@ -0,0 +1,265 @@
+use std::vec::Vec;
+use ndarray::{Array2, Axis};
+use ndarray_extension;
+use random::Rng;
+
+// Small value added to denominators and logarithms
+const EPSILON: f64 = 1e-10;
+
+/// Struct that factors a non-negative document-term matrix X (e.g. the output
+/// of TfidfVectorizer or CountVectorizer) into a document-topic matrix W and
+/// a topic-term matrix H with X ≈ W · H, i.e. Non-negative Matrix
+/// Factorization (NMF) as a topic model. The basic flow follows NMF of
+/// scikit-learn with random initialization.
+///
+/// Two solvers are available. "mu" (multiplicative updates) minimizes either
+/// beta_loss "frobenius" (squared Euclidean distance) or "kullback-leibler"
+/// (generalized KL divergence). "cd" (coordinate descent) updates one
+/// element at a time and minimizes "frobenius" only; it usually converges in
+/// fewer iterations.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::nmf::Nmf;
+/// use vectorizer::tfidfvectorizer::TfidfVectorizer;
+///
+/// let docs = vec![
+///     "the cat sat on the mat",
+///     "the cat chased the dog",
+///     "stocks fell as markets closed",
+///     "markets rallied and stocks rose",
+/// ];
+///
+/// let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+/// let x = vectorizer.fit_transform(docs);
+///
+/// let mut nmf = Nmf::new(2);
+/// let w = nmf.fit_transform(&x);
+/// assert_eq!((4, 2), w.dim());
+/// assert_eq!((2, 15), nmf.components_.dim());
+///
+/// nmf.print_top_terms(&vectorizer.get_feature_names(), 3);
+/// ```
+///
+pub struct Nmf {
+    /// Number of topics.
+    pub n_components: usize,
+
+    /// Algorithm used for the updates. Options are "cd" (coordinate descent)
+    /// and "mu" (multiplicative updates). Default is "cd". Panics on any
+    /// other value.
+    pub solver: String,
+
+    /// Loss minimized by the solver. Options are "frobenius" and
+    /// "kullback-leibler" (requires solver "mu"). Default is "frobenius".
+    /// Panics on any other value.
+    pub beta_loss: String,
+
+    /// Maximum number of iterations. Default is 200.
+    pub max_iter: usize,
+
+    /// Tolerance of the stopping condition: iterations stop when the loss
+    /// improves by less than tol relative to the initial loss. Default is
+    /// 1e-4.
+    pub tol: f64,
+
+    /// Seed of the random initialization. Default is 0.
+    pub seed: u64,
+
+    /// Topic-term matrix H (n_components × number of columns). Computed by
+    /// fit().
+    pub components_: Array2<f64>,
+
+    /// Distance between the training matrix and W · H: the Frobenius norm of
+    /// the difference for "frobenius", and sqrt(2 · divergence) for
+    /// "kullback-leibler".
+    pub reconstruction_err_: f64,
+
+    /// Number of iterations run by fit().
+    pub n_iter_: usize,
+}
+
+impl Nmf {
+
+    /// Create a new instance of Nmf with solver="cd", beta_loss="frobenius",
+    /// max_iter=200, tol=1e-4 and seed=0. Options can be changed via the
+    /// public fields.
+    ///
+    pub fn new(n_components: usize) -> Nmf {
+        Nmf {
+            n_components,
+            solver: "cd".to_string(),
+            beta_loss: "frobenius".to_string(),
+            max_iter: 200,
+            tol: 1e-4,
+            seed: 0,
+            components_: Array2::<f64>::zeros((0, 0)),
+            reconstruction_err_: 0.,
+            n_iter_: 0,
+        }
+    }
+
+    // Check the options and whether the loss is the KL divergence
+    fn _is_kullback_leibler(&self) -> bool {
+        let is_kullback_leibler = match self.beta_loss.as_str() {
+            "frobenius" => false,
+            "kullback-leibler" => true,
+            _ => panic!("unknown beta_loss: {} (options are \"frobenius\" and \"kullback-leibler\")", self.beta_loss),
+        };
+        match self.solver.as_str() {
+            "mu" => {},
+            "cd" if !is_kullback_leibler => {},
+            "cd" => panic!("solver \"cd\" only supports beta_loss \"frobenius\""),
+            _ => panic!("unknown solver: {} (options are \"cd\" and \"mu\")", self.solver),
+        }
+        is_kullback_leibler
+    }
+
+    // Random non-negative matrix scaled to the magnitude of x
+    fn _random_init(&self, x: &Array2<f64>, shape: (usize, usize), rng: &mut Rng) -> Array2<f64> {
+        let scale = (x.mean().unwrap_or(0.) / self.n_components as f64).sqrt();
+        Array2::from_shape_fn(shape, |_| scale * rng.normal().abs())
+    }
+
+    // Loss between x and w · h. Returns the squared Frobenius norm / 2 or the
+    // generalized KL divergence.
+    fn _loss(&self, x: &Array2<f64>, w: &Array2<f64>, h: &Array2<f64>) -> f64 {
+        let wh = w.dot(h);
+        if self._is_kullback_leibler() {
+            x.iter().zip(wh.iter())
+                .map(|(&e, &approx)| {
+                    let approx = approx.max(EPSILON);
+                    let log_ratio = if e > 0. { e * (e / approx).ln() } else { 0. };
+                    log_ratio - e + approx
+                })
+                .sum()
+        } else {
+            x.iter().zip(wh.iter()).map(|(e, approx)| (e - approx).powi(2)).sum::<f64>() / 2.
+        }
+    }
+
+    // Multiplicative update of w (x ≈ w · h)
+    fn _update_w_mu(&self, x: &Array2<f64>, w: &mut Array2<f64>, h: &Array2<f64>) {
+        let (numerator, denominator) = if self._is_kullback_leibler() {
+            let ratio = x / &w.dot(h).mapv(|e| e.max(EPSILON));
+            let h_sums = h.sum_axis(Axis(1)).insert_axis(Axis(0));
+            (ratio.dot(&h.t()), h_sums.broadcast(w.dim()).unwrap().to_owned())
+        } else {
+            (x.dot(&h.t()), w.dot(&h.dot(&h.t())))
+        };
+        w.zip_mut_with(&(numerator / denominator.mapv(|e| e.max(EPSILON))), |e, factor| *e *= factor);
+    }
+
+    // Coordinate descent update of w (x ≈ w · h), one element at a time
+    fn _update_w_cd(&self, x: &Array2<f64>, w: &mut Array2<f64>, h: &Array2<f64>) {
+        let hht = h.dot(&h.t());
+        let xht = x.dot(&h.t());
+        let (num_rows, num_components) = w.dim();
+        for t in 0..num_components {
+            let hessian = hht[[t, t]];
+            if hessian == 0. {
+                continue;
+            }
+            for index_row in 0..num_rows {
+                let gradient = w.row(index_row).dot(&hht.column(t)) - xht[[index_row, t]];
+                w[[index_row, t]] = (w[[index_row, t]] - gradient / hessian).max(0.);
+            }
+        }
+    }
+
+    fn _update_w(&self, x: &Array2<f64>, w: &mut Array2<f64>, h: &Array2<f64>) {
+        match self.solver.as_str() {
+            "mu" => self._update_w_mu(x, w, h),
+            _ => self._update_w_cd(x, w, h),
+        }
+    }
+
+    // Run the updates until convergence and return the number of iterations.
+    // h is updated too if update_h is true, otherwise it stays fixed.
+    fn _solve(&self, x: &Array2<f64>, w: &mut Array2<f64>, h: &mut Array2<f64>, update_h: bool) -> usize {
+        self._is_kullback_leibler();
+        let initial_loss = self._loss(x, w, h);
+        let mut previous_loss = initial_loss;
+        let x_t = x.t().to_owned();
+
+        for iteration in 1..=self.max_iter {
+            self._update_w(x, w, h);
+            if update_h {
+                // Same update on the transposed problem x^T ≈ h^T · w^T
+                let mut h_t = h.t().to_owned();
+                self._update_w(&x_t, &mut h_t, &w.t().to_owned());
+                *h = h_t.reversed_axes();
+            }
+
+            if iteration % 10 == 0 {
+                let loss = self._loss(x, w, h);
+                if initial_loss == 0. || (previous_loss - loss) / initial_loss < self.tol {
+                    return iteration;
+                }
+                previous_loss = loss;
+            }
+        }
+        self.max_iter
+    }
+
+    // Fit the topic-term matrix and return the document-topic matrix
+    fn _fit(&mut self, x: &Array2<f64>) -> Array2<f64> {
+        assert!(x.iter().all(|&e| e >= 0.), "NMF requires a non-negative matrix");
+        let (num_rows, num_columns) = x.dim();
+        let mut rng = Rng::new(self.seed);
+        let mut w = self._random_init(x, (num_rows, self.n_components), &mut rng);
+        let mut h = self._random_init(x, (self.n_components, num_columns), &mut rng);
+
+        self.n_iter_ = self._solve(x, &mut w, &mut h, true);
+        self.reconstruction_err_ = (2. * self._loss(x, &w, &h)).sqrt();
+        self.components_ = h;
+        w
+    }
+
+    /// Learn the topic-term matrix (components_) from a non-negative
+    /// document-term matrix. Panics if the matrix has negative elements or
+    /// if solver or beta_loss are invalid.
+    ///
+    pub fn fit(&mut self, x: &Array2<f64>) {
+        self._fit(x);
+    }
+
+    /// Document-topic matrix W of documents (rows with the columns used by
+    /// fit()), computed with the learned components_ kept fixed.
+    ///
+    pub fn transform(&self, x: &Array2<f64>) -> Array2<f64> {
+        let mut rng = Rng::new(self.seed);
+        let mut w = self._random_init(x, (x.dim().0, self.n_components), &mut rng);
+        let mut h = self.components_.clone();
+        self._solve(x, &mut w, &mut h, false);
+        w
+    }
+
+    /// Fit to a document-term matrix, then return its document-topic matrix
+    /// W. See fit() and transform().
+    ///
+    pub fn fit_transform(&mut self, x: &Array2<f64>) -> Array2<f64> {
+        self._fit(x)
+    }
+
+    /// Terms with the k largest weights of each topic as (term, weight)
+    /// pairs, in descending order of weight. feature_names are the column
+    /// names, e.g. from TfidfVectorizer::get_feature_names().
+    ///
+    pub fn top_terms(&self, feature_names: &[String], k: usize) -> Vec<Vec<(String, f64)>> {
+        ndarray_extension::top_terms(&self.components_, feature_names, k)
+    }
+
+    /// Print the k top terms of each topic, one topic per line. See
+    /// top_terms().
+    ///
+    pub fn print_top_terms(&self, feature_names: &[String], k: usize) {
+        for (index_topic, terms) in self.top_terms(feature_names, k).iter().enumerate() {
+            let terms: Vec<&str> = terms.iter().map(|(term, _)| term.as_str()).collect();
+            println!("Topic #{}: {}", index_topic, terms.join(" "));
+        }
+    }
+}
+
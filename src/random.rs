@@ -0,0 +1,69 @@
+//! Small seedable pseudo random number generator used by the randomized
+//! algorithms of the crate (e.g. randomized SVD). It is SplitMix64, so the
+//! same seed gives the same sequence on every platform.
+
+use std::f64::consts::PI;
+
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+
+    // Create a generator from a seed. Any seed (including 0) is fine.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    // Next uniformly distributed u64
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // Next uniformly distributed f64 in [0, 1)
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Next uniformly distributed usize in [0, n)
+    pub fn gen_range(&mut self, n: usize) -> usize {
+        ((self.next_u64() as u128 * n as u128) >> 64) as usize
+    }
+
+    // Shuffle a slice in place (Fisher-Yates)
+    pub fn shuffle<T>(&mut self, slice: &mut [T]) {
+        for i in (1..slice.len()).rev() {
+            let j = self.gen_range(i + 1);
+            slice.swap(i, j);
+        }
+    }
+
+    // Next standard normally distributed f64 (Box-Muller transform)
+    pub fn normal(&mut self) -> f64 {
+        let u1 = 1. - self.next_f64();
+        let u2 = self.next_f64();
+        (-2. * u1.ln()).sqrt() * (2. * PI * u2).cos()
+    }
+
+    // Next gamma distributed f64 with the given shape (>= 1) and scale 1
+    // (Marsaglia and Tsang, 2000)
+    pub fn gamma(&mut self, shape: f64) -> f64 {
+        let d = shape - 1. / 3.;
+        let c = 1. / (9. * d).sqrt();
+        loop {
+            let x = self.normal();
+            let v = (1. + c * x).powi(3);
+            if v <= 0. {
+                continue;
+            }
+            let u = 1. - self.next_f64();
+            if u.ln() < 0.5 * x * x + d - d * v + d * v.ln() {
+                return d * v;
+            }
+        }
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::HashMap;
+use std::vec::Vec;
+use countvectorizer;
+use ndarray_extension;
+use tfidfvectorizer::TfidfVectorizer;
+use tokenizer::Tokenizer;
+
+/// Entry of a posting list: a document containing a token.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Posting {
+    /// ID of the document, i.e. its position in the indexed collection.
+    pub doc_id: usize,
+
+    /// Number of occurrences of the token in the document.
+    pub tf: u64,
+
+    /// Weight of the token in the document, used for scoring (BM25 term
+    /// weight, or Tf-Idf weight with from_tfidf()).
+    pub weight: f64,
+}
+
+/// Inverted index (token → postings) over a collection of documents,
+/// answering free-text queries with the IDs of the best matching documents.
+/// Queries are tokenized with the same Tokenizer settings as the documents.
+///
+/// By default, documents are ranked by Okapi BM25, with the idf weight
+/// ln(1 + (n - df + 0.5) / (df + 0.5)) as in Lucene: the score of a document
+/// sums, over the query tokens,
+///
+/// idf * tf * (k1 + 1) / (tf + k1 * (1 - b + b * dl / avgdl))
+///
+/// where dl is the number of tokens of the document and avgdl the average
+/// over the collection. An index created by from_tfidf() instead weights
+/// documents and queries with a fitted TfidfVectorizer and scores the
+/// dot product of their Tf-Idf vectors (i.e. the cosine similarity with the
+/// default "l2" norm). Only tokens in the vocabulary of the vectorizer are
+/// then indexed and searchable.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::search::InvertedIndex;
+///
+/// let docs = vec![
+///     "install the toolchain with rustup",
+///     "cargo builds and tests the project",
+///     "update the toolchain with rustup update",
+/// ];
+///
+/// let mut index = InvertedIndex::new((1, 1), "lower");
+/// index.fit(docs);
+///
+/// let results = index.search("Rustup update", 2);
+/// assert_eq!(2, results[0].0);
+/// assert_eq!(0, results[1].0);
+/// assert!(index.search("unknown words", 2).is_empty());
+/// ```
+///
+pub struct InvertedIndex {
+    /// A range of n-values for n-grams to be included. See also
+    /// tokenizer::Tokenizer for details.
+    pub ngram_range: (u32, u32),
+
+    /// The case of the resulting tokens. Options are "upper" and "lower".
+    /// Other inputs will use default. See also tokenizer::Tokenizer for
+    /// details.
+    pub case: String,
+
+    /// BM25 term frequency saturation. Default is 1.2.
+    pub k1: f64,
+
+    /// BM25 document length normalization, between 0 (none) and 1 (full).
+    /// Default is 0.75.
+    pub b: f64,
+
+    /// Posting list of each token, in ascending order of doc_id.
+    pub postings_: HashMap<String, Vec<Posting>>,
+
+    /// Number of tokens of each indexed document.
+    pub doc_lengths_: Vec<u64>,
+
+    /// Average number of tokens per indexed document.
+    pub avg_doc_length_: f64,
+
+    // Fitted vectorizer weighting documents and queries instead of BM25
+    vectorizer: Option<TfidfVectorizer>,
+}
+
+impl InvertedIndex {
+
+    /// Create a new instance of InvertedIndex ranking documents by BM25, with
+    /// k1=1.2 and b=0.75. Options can be changed via the public fields
+    /// before fitting.
+    ///
+    pub fn new(ngram_range: (u32, u32), case: &str) -> InvertedIndex {
+        InvertedIndex {
+            ngram_range,
+            case: case.to_string(),
+            k1: 1.2,
+            b: 0.75,
+            postings_: HashMap::new(),
+            doc_lengths_: Vec::new(),
+            avg_doc_length_: 0.,
+            vectorizer: None,
+        }
+    }
+
+    /// Create a new instance of InvertedIndex ranking documents by the Tf-Idf
+    /// weights of a fitted TfidfVectorizer. The ngram_range and case of the
+    /// vectorizer are used for tokenization.
+    ///
+    pub fn from_tfidf(vectorizer: TfidfVectorizer) -> InvertedIndex {
+        let mut index = InvertedIndex::new(vectorizer.ngram_range, vectorizer.case.as_str());
+        index.vectorizer = Some(vectorizer);
+        index
+    }
+
+    fn _tokenize(&self, docs: Vec<&str>) -> Vec<Vec<String>> {
+        Tokenizer::new(self.ngram_range, self.case.as_str()).tokenize(docs)
+    }
+
+    // Counts of the tokens of a tokenized document in order of first
+    // occurrence
+    fn _count(tokens: &[String]) -> Vec<(&str, u64)> {
+        let mut counts: Vec<(&str, u64)> = Vec::new();
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for token in tokens {
+            match positions.get(token.as_str()) {
+                Some(&position) => counts[position].1 += 1,
+                None => {
+                    positions.insert(token.as_str(), counts.len());
+                    counts.push((token.as_str(), 1));
+                },
+            }
+        }
+        counts
+    }
+
+    /// Index a collection of documents. Document IDs are the positions in
+    /// docs. Any previously indexed documents are discarded.
+    ///
+    pub fn fit(&mut self, docs: Vec<&str>) {
+        let tokenized_docs = self._tokenize(docs);
+        let num_docs = tokenized_docs.len();
+        self.doc_lengths_ = tokenized_docs.iter().map(|doc| doc.len() as u64).collect();
+        self.avg_doc_length_ = if num_docs > 0 {
+            self.doc_lengths_.iter().sum::<u64>() as f64 / num_docs as f64
+        } else {
+            0.
+        };
+
+        let mut postings: HashMap<String, Vec<Posting>> = HashMap::new();
+        match self.vectorizer {
+            // Weights from the Tf-Idf matrix of the counts of the vocabulary
+            Some(ref vectorizer) => {
+                // u64 counts cannot overflow
+                let counts = countvectorizer::count_tokens(&vectorizer.vocabulary_, tokenized_docs).unwrap();
+                let tfidf = vectorizer.transform_counts(&counts);
+                let feature_names = vectorizer.get_feature_names();
+                for ((doc_id, index_col), &tf) in counts.indexed_iter() {
+                    if tf > 0 {
+                        let weight = tfidf[[doc_id, index_col]];
+                        postings.entry(feature_names[index_col].clone())
+                            .or_default()
+                            .push(Posting { doc_id, tf, weight });
+                    }
+                }
+            },
+            // BM25 weights are set once all the postings are known
+            None => {
+                for (doc_id, doc) in tokenized_docs.iter().enumerate() {
+                    for (token, tf) in InvertedIndex::_count(doc) {
+                        postings.entry(token.to_string())
+                            .or_default()
+                            .push(Posting { doc_id, tf, weight: 0. });
+                    }
+                }
+            },
+        }
+        self.postings_ = postings;
+
+        if self.vectorizer.is_none() {
+            self._set_bm25_weights();
+        }
+    }
+
+    fn _set_bm25_weights(&mut self) {
+        let num_docs = self.doc_lengths_.len() as f64;
+        let (k1, b, avg_doc_length) = (self.k1, self.b, self.avg_doc_length_);
+        let doc_lengths = &self.doc_lengths_;
+        for posting_list in self.postings_.values_mut() {
+            let df = posting_list.len() as f64;
+            let idf = (1. + (num_docs - df + 0.5) / (df + 0.5)).ln();
+            for posting in posting_list.iter_mut() {
+                let tf = posting.tf as f64;
+                let length_ratio = doc_lengths[posting.doc_id] as f64 / avg_doc_length;
+                posting.weight = idf * tf * (k1 + 1.) / (tf + k1 * (1. - b + b * length_ratio));
+            }
+        }
+    }
+
+    /// Number of indexed documents.
+    ///
+    pub fn num_docs(&self) -> usize {
+        self.doc_lengths_.len()
+    }
+
+    /// The k best matching documents of a free-text query as (doc_id, score)
+    /// pairs, in descending order of score (ties in ascending order of
+    /// doc_id). Documents sharing no token with the query are not returned.
+    ///
+    pub fn search(&self, query: &str, k: usize) -> Vec<(usize, f64)> {
+        let tokens = self._tokenize(vec![query]).pop().unwrap_or_default();
+
+        // Weight of each query token
+        let query_weights: Vec<(&str, f64)> = match self.vectorizer {
+            Some(ref vectorizer) => {
+                let tfidf = vectorizer.transform(vec![query]);
+                InvertedIndex::_count(&tokens).into_iter()
+                    .filter_map(|(token, _)| vectorizer.vocabulary_.get(token)
+                        .map(|&index_col| (token, tfidf[[0, index_col as usize]])))
+                    .collect()
+            },
+            None => InvertedIndex::_count(&tokens).into_iter()
+                .map(|(token, count)| (token, count as f64))
+                .collect(),
+        };
+
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for (token, query_weight) in query_weights {
+            if let Some(posting_list) = self.postings_.get(token) {
+                for posting in posting_list {
+                    *scores.entry(posting.doc_id).or_insert(0.) += query_weight * posting.weight;
+                }
+            }
+        }
+        ndarray_extension::top_k(scores.into_iter().collect(), k)
+    }
+}
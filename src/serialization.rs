@@ -0,0 +1,305 @@
+//! Save and load fitted vectorizers (requires the "serde" feature).
+//!
+//! A saved vectorizer holds its configuration, the vocabulary ordered by
+//! token ID, the stop words removed before counting and, for
+//! TfidfVectorizer, the fitted idf weights and document frequencies, so that a loaded vectorizer transforms documents exactly as
+//! the original one. Every file starts with FORMAT_VERSION, and files with
+//! another version are rejected by load().
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use ndarray::Array1;
+use serde::{Deserialize, Serialize};
+use bincode;
+use serde_json;
+use countvectorizer::CountVectorizer;
+use tfidfvectorizer::TfidfVectorizer;
+
+/// Version of the saved format. Incremented whenever the saved fields change.
+/// Version 1 records the stop words of a vectorizer, which are always empty
+/// for now since the tokenizer removes none; load() rejects files with stop
+/// words.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Encoding of a saved vectorizer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    /// Human readable JSON.
+    Json,
+
+    /// Compact binary encoding (bincode).
+    Binary,
+}
+
+/// Error returned by save() and load().
+#[derive(Debug)]
+pub enum SerializationError {
+    /// Reading or writing failed.
+    Io(io::Error),
+
+    /// The JSON encoding is invalid.
+    Json(serde_json::Error),
+
+    /// The binary encoding is invalid.
+    Binary(bincode::Error),
+
+    /// The saved format version is not FORMAT_VERSION.
+    UnsupportedVersion(u32),
+
+    /// The saved fields are inconsistent (e.g. a duplicated token, or idf
+    /// weights not matching the vocabulary), with a description.
+    InvalidModel(String),
+}
+
+impl fmt::Display for SerializationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SerializationError::Io(e) => write!(f, "I/O error: {}", e),
+            SerializationError::Json(e) => write!(f, "invalid JSON: {}", e),
+            SerializationError::Binary(e) => write!(f, "invalid binary encoding: {}", e),
+            SerializationError::UnsupportedVersion(version) => write!(
+                f, "unsupported format version {} (expected {})", version, FORMAT_VERSION),
+            SerializationError::InvalidModel(message) => write!(f, "invalid saved vectorizer: {}", message),
+        }
+    }
+}
+
+impl Error for SerializationError {}
+
+impl From<io::Error> for SerializationError {
+    fn from(e: io::Error) -> SerializationError {
+        SerializationError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for SerializationError {
+    fn from(e: serde_json::Error) -> SerializationError {
+        SerializationError::Json(e)
+    }
+}
+
+impl From<bincode::Error> for SerializationError {
+    fn from(e: bincode::Error) -> SerializationError {
+        SerializationError::Binary(e)
+    }
+}
+
+
+// Saved content of a CountVectorizer
+#[derive(Serialize, Deserialize)]
+struct CountVectorizerModel {
+    format_version: u32,
+    ngram_range: (u32, u32),
+    case: String,
+    stop_words: Vec<String>,
+    vocabulary: Vec<String>,
+}
+
+// Saved content of a TfidfVectorizer
+#[derive(Serialize, Deserialize)]
+struct TfidfVectorizerModel {
+    format_version: u32,
+    ngram_range: (u32, u32),
+    case: String,
+    smooth_idf: bool,
+    sublinear_tf: bool,
+    norm: String,
+    pivot: Option<f64>,
+    slope: f64,
+    stop_words: Vec<String>,
+    vocabulary: Vec<String>,
+    idf: Vec<f64>,
+    pivot_fitted: f64,
+    df: Vec<u64>,
+    n_samples: u64,
+}
+
+// Stop words cannot be applied by the tokenizer yet, so only an empty
+// record is accepted
+fn _validate_stop_words(stop_words: &[String]) -> Result<(), SerializationError> {
+    if !stop_words.is_empty() {
+        return Err(SerializationError::InvalidModel(format!(
+            "{} stop words, but stop words are not supported", stop_words.len())));
+    }
+    Ok(())
+}
+
+impl TfidfVectorizerModel {
+    // Check that the fitted fields agree with each other and with the
+    // vocabulary
+    fn _validate(&self) -> Result<(), SerializationError> {
+        _validate_stop_words(&self.stop_words)?;
+        let num_tokens = self.vocabulary.len();
+        let fitted = !(self.idf.is_empty() && self.df.is_empty());
+        if fitted && (self.idf.len() != num_tokens || self.df.len() != num_tokens) {
+            return Err(SerializationError::InvalidModel(format!(
+                "{} idf weights and {} document frequencies for {} tokens",
+                self.idf.len(), self.df.len(), num_tokens)));
+        }
+        let pivot_consistent = match self.pivot {
+            _ if self.n_samples == 0 => self.pivot_fitted == 0.,
+            Some(pivot) => self.pivot_fitted == pivot,
+            None => self.pivot_fitted.is_finite() && self.pivot_fitted >= 0.,
+        };
+        if !pivot_consistent {
+            return Err(SerializationError::InvalidModel(format!(
+                "fitted pivot {} does not match pivot {:?} and {} documents",
+                self.pivot_fitted, self.pivot, self.n_samples)));
+        }
+        Ok(())
+    }
+}
+
+// Vec of tokens ordered by token ID as a vocabulary HashMap. Duplicated
+// tokens would leave IDs without a token, so they are rejected.
+fn _vec_to_vocabulary(tokens: Vec<String>) -> Result<HashMap<String, u64>, SerializationError> {
+    let num_tokens = tokens.len();
+    let vocabulary: HashMap<String, u64> = tokens.into_iter()
+        .enumerate()
+        .map(|(index, token)| (token, index as u64))
+        .collect();
+    if vocabulary.len() != num_tokens {
+        return Err(SerializationError::InvalidModel(format!(
+            "vocabulary has {} duplicated tokens", num_tokens - vocabulary.len())));
+    }
+    Ok(vocabulary)
+}
+
+// Write the format version followed by the model
+fn _save<W: Write, M: Serialize>(model: &M, mut writer: W, format: Format) -> Result<(), SerializationError> {
+    match format {
+        Format::Json => serde_json::to_writer(&mut writer, model)?,
+        Format::Binary => {
+            bincode::serialize_into(&mut writer, &FORMAT_VERSION)?;
+            bincode::serialize_into(&mut writer, model)?;
+        },
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+// Read a model after checking its format version
+fn _load<R: Read, M>(mut reader: R, format: Format) -> Result<M, SerializationError>
+    where M: for<'de> Deserialize<'de>
+{
+    match format {
+        Format::Json => {
+            let value: serde_json::Value = serde_json::from_reader(reader)?;
+            let version = value.get("format_version")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0) as u32;
+            if version != FORMAT_VERSION {
+                return Err(SerializationError::UnsupportedVersion(version));
+            }
+            Ok(serde_json::from_value(value)?)
+        },
+        Format::Binary => {
+            let version: u32 = bincode::deserialize_from(&mut reader)?;
+            if version != FORMAT_VERSION {
+                return Err(SerializationError::UnsupportedVersion(version));
+            }
+            Ok(bincode::deserialize_from(reader)?)
+        },
+    }
+}
+
+
+impl CountVectorizer {
+
+    /// Save the configuration and vocabulary to a writer (e.g. a File) in
+    /// the given format.
+    ///
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    ///
+    /// use vectorizer::countvectorizer::CountVectorizer;
+    /// use vectorizer::serialization::Format;
+    ///
+    /// let docs = vec!["apple banana apple", "banana orange"];
+    /// let mut vectorizer = CountVectorizer::new((1, 2), "lower");
+    /// vectorizer.fit(docs.clone());
+    ///
+    /// let mut saved: Vec<u8> = Vec::new();
+    /// vectorizer.save(&mut saved, Format::Json).unwrap();
+    /// let loaded = CountVectorizer::load(saved.as_slice(), Format::Json).unwrap();
+    ///
+    /// assert_eq!(vectorizer.transform(docs.clone()), loaded.transform(docs));
+    /// ```
+    ///
+    pub fn save<W: Write>(&self, writer: W, format: Format) -> Result<(), SerializationError> {
+        let model = CountVectorizerModel {
+            format_version: FORMAT_VERSION,
+            ngram_range: self.ngram_range,
+            case: self.case.clone(),
+            stop_words: Vec::new(),
+            vocabulary: self.get_feature_names(),
+        };
+        _save(&model, writer, format)
+    }
+
+    /// Load a CountVectorizer saved by save() from a reader. Returns
+    /// InvalidModel if the vocabulary has duplicated tokens or if stop words
+    /// are recorded.
+    ///
+    pub fn load<R: Read>(reader: R, format: Format) -> Result<CountVectorizer, SerializationError> {
+        let model: CountVectorizerModel = _load(reader, format)?;
+        _validate_stop_words(&model.stop_words)?;
+        let mut vectorizer = CountVectorizer::new(model.ngram_range, model.case.as_str());
+        vectorizer.vocabulary_ = _vec_to_vocabulary(model.vocabulary)?;
+        Ok(vectorizer)
+    }
+}
+
+
+impl TfidfVectorizer {
+
+    /// Save the configuration, vocabulary, idf weights and document
+    /// frequencies to a writer (e.g. a File) in the given format. See also
+    /// CountVectorizer::save().
+    ///
+    pub fn save<W: Write>(&self, writer: W, format: Format) -> Result<(), SerializationError> {
+        let model = TfidfVectorizerModel {
+            format_version: FORMAT_VERSION,
+            ngram_range: self.ngram_range,
+            case: self.case.clone(),
+            smooth_idf: self.smooth_idf,
+            sublinear_tf: self.sublinear_tf,
+            norm: self.norm.clone(),
+            pivot: self.pivot,
+            slope: self.slope,
+            stop_words: Vec::new(),
+            vocabulary: self.get_feature_names(),
+            idf: self.idf_.to_vec(),
+            pivot_fitted: self.pivot_,
+            df: self.df_.to_vec(),
+            n_samples: self.n_samples_,
+        };
+        _save(&model, writer, format)
+    }
+
+    /// Load a TfidfVectorizer saved by save() from a reader. The loaded
+    /// vectorizer can transform() right away, and partial_fit() continues
+    /// from the saved document frequencies. Returns InvalidModel if the
+    /// vocabulary, idf weights, document frequencies and fitted pivot do
+    /// not agree, or if stop words are recorded.
+    ///
+    pub fn load<R: Read>(reader: R, format: Format) -> Result<TfidfVectorizer, SerializationError> {
+        let model: TfidfVectorizerModel = _load(reader, format)?;
+        model._validate()?;
+        let mut vectorizer = TfidfVectorizer::new(model.ngram_range, model.case.as_str());
+        vectorizer.smooth_idf = model.smooth_idf;
+        vectorizer.sublinear_tf = model.sublinear_tf;
+        vectorizer.norm = model.norm;
+        vectorizer.pivot = model.pivot;
+        vectorizer.slope = model.slope;
+        vectorizer.vocabulary_ = _vec_to_vocabulary(model.vocabulary)?;
+        vectorizer.idf_ = Array1::from(model.idf);
+        vectorizer.pivot_ = model.pivot_fitted;
+        vectorizer.df_ = Array1::from(model.df);
+        vectorizer.n_samples_ = model.n_samples;
+        Ok(vectorizer)
+    }
+}
@@ -0,0 +1,264 @@
+use std::vec::Vec;
+use hashingvectorizer::murmurhash3_32;
+use tfidfvectorizer::TfidfVectorizer;
+
+/// 64-bit hash of a token: the murmurhash3_32() of its UTF-8 bytes with
+/// seeds 0 and 1 as high and low halves.
+///
+pub fn hash64(token: &str) -> u64 {
+    let high = murmurhash3_32(token.as_bytes(), 0) as u64;
+    let low = murmurhash3_32(token.as_bytes(), 1) as u64;
+    (high << 32) | low
+}
+
+/// Number of differing bits of two fingerprints.
+///
+pub fn hamming_distance(a: u64, b: u64) -> u32 {
+    (a ^ b).count_ones()
+}
+
+/// SimHash fingerprint of weighted tokens (Charikar, 2002): bit i is set if
+/// the summed weights of the tokens whose hash64() has bit i set exceed
+/// those of the tokens without it. Similar weighted token sets get
+/// fingerprints at a small Hamming distance.
+///
+/// # Examples
+/// ```
+/// use vectorizer::simhash;
+///
+/// let a = simhash::fingerprint(&[("quick", 1.0), ("brown", 0.5), ("fox", 2.0)]);
+/// let b = simhash::fingerprint(&[("quick", 1.0), ("brown", 0.4), ("fox", 2.0)]);
+/// assert_eq!(a, b);
+/// assert_eq!(simhash::hash64("fox"), simhash::fingerprint(&[("fox", 1.0)]));
+/// ```
+///
+pub fn fingerprint(weighted_tokens: &[(&str, f64)]) -> u64 {
+    let hashes: Vec<(u64, f64)> = weighted_tokens.iter()
+        .map(|&(token, weight)| (hash64(token), weight))
+        .collect();
+    _fingerprint(&hashes)
+}
+
+// SimHash fingerprint of weighted token hashes
+fn _fingerprint(weighted_hashes: &[(u64, f64)]) -> u64 {
+    let mut sums = [0f64; 64];
+    for &(hash, weight) in weighted_hashes {
+        for (bit, sum) in sums.iter_mut().enumerate() {
+            if hash >> bit & 1 == 1 {
+                *sum += weight;
+            } else {
+                *sum -= weight;
+            }
+        }
+    }
+    sums.iter()
+        .enumerate()
+        .filter(|&(_, &sum)| sum > 0.)
+        .fold(0, |fingerprint, (bit, _)| fingerprint | 1 << bit)
+}
+
+
+/// Computes 64-bit SimHash fingerprints of documents (i.e. Vec of string
+/// slices) from the weights given to their tokens by a fitted
+/// TfidfVectorizer, so that rare tokens weigh more than common ones. Near
+/// duplicate documents get fingerprints within a few bits of each other,
+/// which SimHashIndex retrieves without comparing all pairs. Tokens outside
+/// of the vocabulary of the vectorizer are ignored.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::simhash::{self, SimHash};
+/// use vectorizer::tfidfvectorizer::TfidfVectorizer;
+///
+/// let docs = vec![
+///     "the central bank raised interest rates by a quarter point today",
+///     "the central bank raised interest rates by a quarter point on monday",
+///     "local team wins the championship after a dramatic final match",
+/// ];
+///
+/// let mut vectorizer = TfidfVectorizer::new((1, 2), "lower");
+/// vectorizer.fit(docs.clone());
+///
+/// let simhash = SimHash::new(vectorizer);
+/// let fingerprints = simhash.transform(docs);
+///
+/// let near = simhash::hamming_distance(fingerprints[0], fingerprints[1]);
+/// let far = simhash::hamming_distance(fingerprints[0], fingerprints[2]);
+/// assert!(near < far);
+/// ```
+///
+pub struct SimHash {
+    vectorizer: TfidfVectorizer,
+
+    // hash64() of each column of the vectorizer
+    token_hashes: Vec<u64>,
+}
+
+impl SimHash {
+
+    /// Create a new instance of SimHash weighting tokens by a fitted
+    /// TfidfVectorizer.
+    ///
+    pub fn new(vectorizer: TfidfVectorizer) -> SimHash {
+        let token_hashes = vectorizer.get_feature_names().iter()
+            .map(|token| hash64(token))
+            .collect();
+        SimHash { vectorizer, token_hashes }
+    }
+
+    /// The TfidfVectorizer weighting the tokens.
+    ///
+    pub fn vectorizer(&self) -> &TfidfVectorizer {
+        &self.vectorizer
+    }
+
+    /// Fingerprint of each document. Documents without any known token get a
+    /// fingerprint of 0.
+    ///
+    pub fn transform(&self, docs: Vec<&str>) -> Vec<u64> {
+        self.vectorizer.transform(docs).outer_iter()
+            .map(|row| {
+                let weighted_hashes: Vec<(u64, f64)> = row.iter()
+                    .zip(self.token_hashes.iter())
+                    .filter(|&(&weight, _)| weight != 0.)
+                    .map(|(&weight, &hash)| (hash, weight))
+                    .collect();
+                _fingerprint(&weighted_hashes)
+            })
+            .collect()
+    }
+}
+
+
+// Table of the fingerprints with one block rotated to the highest bits
+struct PermutedTable {
+    // Left rotation moving the block to the highest bits
+    rotation: u32,
+
+    // Number of bits of the block
+    width: u32,
+
+    // Rotated fingerprints and their IDs, sorted
+    entries: Vec<(u64, usize)>,
+}
+
+
+/// Index of 64-bit fingerprints retrieving those within k bits (Hamming
+/// distance) of a query, with the permuted tables of Manku et al. (2007).
+/// Fingerprints are split into k + 1 blocks of bits, and two fingerprints
+/// within k bits agree on at least one whole block. Each table keeps the
+/// fingerprints sorted with one block moved to the highest bits, so that
+/// the fingerprints sharing that block with a query are a contiguous range
+/// found by binary search. Candidates are then checked bit by bit.
+///
+/// # Examples
+/// ```
+/// use vectorizer::simhash::SimHashIndex;
+///
+/// let fingerprints = vec![0b1011_0000, 0b1011_0011, u64::MAX, 0];
+///
+/// let mut index = SimHashIndex::new(2);
+/// index.fit(&fingerprints);
+///
+/// assert_eq!(vec![(0, 0), (1, 2)], index.query(0b1011_0000));
+/// assert_eq!(vec![(0, 1, 2)], index.near_duplicate_pairs());
+/// ```
+///
+pub struct SimHashIndex {
+    /// Maximum Hamming distance of retrieved fingerprints, below 64.
+    pub k: u32,
+
+    /// Indexed fingerprints. Document IDs are their positions.
+    pub fingerprints_: Vec<u64>,
+
+    // One table per block
+    tables: Vec<PermutedTable>,
+}
+
+impl SimHashIndex {
+
+    /// Create a new instance of SimHashIndex retrieving fingerprints within k
+    /// bits. Panics if k is not below 64.
+    ///
+    pub fn new(k: u32) -> SimHashIndex {
+        assert!(k < 64, "k must be below 64");
+        let num_blocks = k + 1;
+        let tables = (0..num_blocks)
+            .map(|index_block| {
+                // Blocks of 64 / num_blocks bits, the first ones one bit wider
+                let width = 64 / num_blocks + (index_block < 64 % num_blocks) as u32;
+                let start = index_block * (64 / num_blocks) + index_block.min(64 % num_blocks);
+                let rotation = (64 - (start + width)) % 64;
+                PermutedTable { rotation, width, entries: Vec::new() }
+            })
+            .collect();
+        SimHashIndex {
+            k,
+            fingerprints_: Vec::new(),
+            tables,
+        }
+    }
+
+    /// Index fingerprints. Any previously indexed fingerprints are
+    /// discarded. See also partial_fit().
+    ///
+    pub fn fit(&mut self, fingerprints: &[u64]) {
+        self.fingerprints_.clear();
+        for table in self.tables.iter_mut() {
+            table.entries.clear();
+        }
+        self.partial_fit(fingerprints);
+    }
+
+    /// Add fingerprints to the index. Their document IDs follow those of
+    /// the already indexed fingerprints.
+    ///
+    pub fn partial_fit(&mut self, fingerprints: &[u64]) {
+        let first_id = self.fingerprints_.len();
+        for table in self.tables.iter_mut() {
+            let rotation = table.rotation;
+            table.entries.extend(fingerprints.iter()
+                .enumerate()
+                .map(|(offset, fingerprint)| (fingerprint.rotate_left(rotation), first_id + offset)));
+            table.entries.sort_unstable();
+        }
+        self.fingerprints_.extend_from_slice(fingerprints);
+    }
+
+    /// IDs of the indexed fingerprints within k bits of a fingerprint, with
+    /// their distances, in ascending order of ID.
+    ///
+    pub fn query(&self, fingerprint: u64) -> Vec<(usize, u32)> {
+        let mut doc_ids: Vec<usize> = Vec::new();
+        for table in self.tables.iter() {
+            let mask = u64::MAX.checked_shr(table.width).map_or(u64::MAX, |low| !low);
+            let prefix = fingerprint.rotate_left(table.rotation) & mask;
+            let begin = table.entries.partition_point(|&(rotated, _)| rotated & mask < prefix);
+            doc_ids.extend(table.entries[begin..].iter()
+                .take_while(|&&(rotated, _)| rotated & mask == prefix)
+                .map(|&(_, doc_id)| doc_id));
+        }
+        doc_ids.sort_unstable();
+        doc_ids.dedup();
+        doc_ids.into_iter()
+            .map(|doc_id| (doc_id, hamming_distance(fingerprint, self.fingerprints_[doc_id])))
+            .filter(|&(_, distance)| distance <= self.k)
+            .collect()
+    }
+
+    /// Pairs (i, j) of indexed fingerprints with i < j within k bits, with
+    /// their distances, in ascending order of (i, j).
+    ///
+    pub fn near_duplicate_pairs(&self) -> Vec<(usize, usize, u32)> {
+        self.fingerprints_.iter()
+            .enumerate()
+            .flat_map(|(i, &fingerprint)| {
+                self.query(fingerprint).into_iter()
+                    .filter(move |&(j, _)| j > i)
+                    .map(move |(j, distance)| (i, j, distance))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,233 @@
+use std::vec::Vec;
+use ndarray::{Array2, ArrayBase, Data, Ix2, LinalgScalar};
+use num::Zero;
+
+/// A sparse matrix in Compressed Sparse Row (CSR) format, following the
+/// layout of scipy.sparse.csr_matrix. The non-zero elements of the i-th row
+/// are data[indptr[i]..indptr[i + 1]], and their column indices are
+/// indices[indptr[i]..indptr[i + 1]] (sorted in ascending order).
+///
+pub struct CsrMatrix<T> {
+    /// (number of rows, number of columns)
+    pub shape: (usize, usize),
+
+    /// Offsets into indices and data where each row starts. Its length is
+    /// the number of rows + 1.
+    pub indptr: Vec<usize>,
+
+    /// Column index of each stored element.
+    pub indices: Vec<usize>,
+
+    /// Value of each stored element.
+    pub data: Vec<T>,
+}
+
+impl<T: Clone + Zero> CsrMatrix<T> {
+
+    /// Create a CsrMatrix from a Vec of rows, where each row is a Vec of
+    /// (column index, value) pairs. Pairs do not need to be sorted, but
+    /// column indices must be unique within a row and smaller than
+    /// num_columns. Zero values are not stored.
+    ///
+    /// # Examples
+    /// ```
+    /// extern crate ndarray;
+    /// extern crate vectorizer;
+    ///
+    /// use ndarray::arr2;
+    /// use vectorizer::sparse::CsrMatrix;
+    ///
+    /// let rows = vec![
+    ///     vec![(2, 1.0), (0, 3.0)],
+    ///     vec![],
+    ///     vec![(1, 2.0)]];
+    /// let x = CsrMatrix::from_rows(rows, 3);
+    ///
+    /// assert_eq!((3, 3), x.dim());
+    /// assert_eq!(3, x.nnz());
+    /// assert_eq!(arr2(&[
+    ///     [3.0, 0.0, 1.0],
+    ///     [0.0, 0.0, 0.0],
+    ///     [0.0, 2.0, 0.0]]), x.to_dense());
+    /// ```
+    ///
+    pub fn from_rows(rows: Vec<Vec<(usize, T)>>, num_columns: usize) -> CsrMatrix<T> {
+        let mut indptr: Vec<usize> = Vec::with_capacity(rows.len() + 1);
+        let mut indices: Vec<usize> = Vec::new();
+        let mut data: Vec<T> = Vec::new();
+        indptr.push(0);
+
+        let num_rows = rows.len();
+        for mut row in rows {
+            row.sort_by_key(|&(index_col, _)| index_col);
+            for (index_col, value) in row {
+                assert!(index_col < num_columns, "column index out of bounds");
+                if !value.is_zero() {
+                    indices.push(index_col);
+                    data.push(value);
+                }
+            }
+            indptr.push(indices.len());
+        }
+
+        CsrMatrix {
+            shape: (num_rows, num_columns),
+            indptr,
+            indices,
+            data,
+        }
+    }
+
+    /// Create a CsrMatrix from the non-zero elements of a dense Array2<T>.
+    ///
+    pub fn from_dense(matrix: &Array2<T>) -> CsrMatrix<T> {
+        let (_, num_columns) = matrix.dim();
+        let rows = matrix.outer_iter()
+            .map(|row| {
+                row.iter()
+                    .enumerate()
+                    .filter(|&(_, e)| !e.is_zero())
+                    .map(|(index_col, e)| (index_col, e.clone()))
+                    .collect()
+            })
+            .collect();
+        CsrMatrix::from_rows(rows, num_columns)
+    }
+
+    /// Convert into a dense Array2<T>.
+    ///
+    pub fn to_dense(&self) -> Array2<T> {
+        let mut matrix = Array2::<T>::zeros(self.shape);
+        for index_row in 0..self.shape.0 {
+            let (indices, data) = self.row(index_row);
+            for (index_col, value) in indices.iter().zip(data.iter()) {
+                matrix[[index_row, *index_col]] = value.clone();
+            }
+        }
+        matrix
+    }
+}
+
+impl<T> CsrMatrix<T> {
+
+    /// Shape of the matrix as (number of rows, number of columns).
+    ///
+    pub fn dim(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    /// Number of stored (non-zero) elements.
+    ///
+    pub fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Column indices and values of the stored elements of a row.
+    ///
+    pub fn row(&self, index_row: usize) -> (&[usize], &[T]) {
+        let range = self.indptr[index_row]..self.indptr[index_row + 1];
+        (&self.indices[range.clone()], &self.data[range])
+    }
+}
+
+impl<T: Clone> CsrMatrix<T> {
+
+    /// Transposed matrix, also in CSR format. The rows of the transposed
+    /// matrix are the columns of the original one, which gives fast access
+    /// to all the rows containing a given column.
+    ///
+    pub fn transpose(&self) -> CsrMatrix<T> {
+        let (num_rows, num_columns) = self.shape;
+
+        // Count elements per column to build indptr
+        let mut indptr = vec![0; num_columns + 1];
+        for index_col in self.indices.iter() {
+            indptr[index_col + 1] += 1;
+        }
+        for index_col in 0..num_columns {
+            indptr[index_col + 1] += indptr[index_col];
+        }
+
+        // Scatter elements; rows are visited in order so indices stay sorted
+        let mut next = indptr.clone();
+        let mut indices = vec![0; self.nnz()];
+        let mut data: Vec<Option<T>> = vec![None; self.nnz()];
+        for index_row in 0..num_rows {
+            let (row_indices, row_data) = self.row(index_row);
+            for (index_col, value) in row_indices.iter().zip(row_data.iter()) {
+                let position = next[*index_col];
+                indices[position] = index_row;
+                data[position] = Some(value.clone());
+                next[*index_col] += 1;
+            }
+        }
+
+        CsrMatrix {
+            shape: (num_columns, num_rows),
+            indptr,
+            indices,
+            data: data.into_iter().map(|e| e.unwrap()).collect(),
+        }
+    }
+}
+
+impl<T: LinalgScalar> CsrMatrix<T> {
+
+    /// Product with a dense matrix (self · other) as a dense Array2<T>.
+    ///
+    /// # Examples
+    /// ```
+    /// extern crate ndarray;
+    /// extern crate vectorizer;
+    ///
+    /// use ndarray::arr2;
+    /// use vectorizer::sparse::CsrMatrix;
+    ///
+    /// let x = arr2(&[
+    ///     [1.0, 0.0, 2.0],
+    ///     [0.0, 3.0, 0.0]]);
+    /// let y = arr2(&[
+    ///     [1.0, 2.0],
+    ///     [3.0, 4.0],
+    ///     [5.0, 6.0]]);
+    /// let x_sparse = CsrMatrix::from_dense(&x);
+    ///
+    /// assert_eq!(x.dot(&y), x_sparse.dot(&y));
+    /// assert_eq!(x.t().dot(&x), x_sparse.transpose_dot(&x));
+    /// ```
+    ///
+    pub fn dot<S: Data<Elem = T>>(&self, other: &ArrayBase<S, Ix2>) -> Array2<T> {
+        let (num_rows, num_columns) = self.shape;
+        let (other_rows, other_columns) = other.dim();
+        assert_eq!(num_columns, other_rows, "inconsistent shapes for dot()");
+
+        let mut product = Array2::<T>::zeros((num_rows, other_columns));
+        for index_row in 0..num_rows {
+            let (indices, data) = self.row(index_row);
+            let mut product_row = product.row_mut(index_row);
+            for (index_col, value) in indices.iter().zip(data.iter()) {
+                product_row.scaled_add(*value, &other.row(*index_col));
+            }
+        }
+        product
+    }
+
+    /// Product of the transposed matrix with a dense matrix (self^T · other)
+    /// as a dense Array2<T>, without building the transposed matrix.
+    ///
+    pub fn transpose_dot<S: Data<Elem = T>>(&self, other: &ArrayBase<S, Ix2>) -> Array2<T> {
+        let (num_rows, num_columns) = self.shape;
+        let (other_rows, other_columns) = other.dim();
+        assert_eq!(num_rows, other_rows, "inconsistent shapes for transpose_dot()");
+
+        let mut product = Array2::<T>::zeros((num_columns, other_columns));
+        for index_row in 0..num_rows {
+            let (indices, data) = self.row(index_row);
+            let other_row = other.row(index_row);
+            for (index_col, value) in indices.iter().zip(data.iter()) {
+                product.row_mut(*index_col).scaled_add(*value, &other_row);
+            }
+        }
+        product
+    }
+}
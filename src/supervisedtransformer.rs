@@ -0,0 +1,172 @@
+use std::vec::Vec;
+use ndarray::{Array1, Array2};
+use naivebayes::encode_labels;
+use tfidftransformer;
+
+/// Transforms a count matrix (e.g. the output of CountVectorizer) into a
+/// matrix weighted by supervised term weights learned from binary labels,
+/// in place of the idf weights of TfidfTransformer. Each row is a document
+/// and each column a token. The first sorted label (classes_[0]) is the
+/// negative class and the second (classes_[1]) the positive class.
+///
+/// With P and N the number of positive and negative documents, and P_t and
+/// N_t the number of those containing the term, the schemes are:
+///
+/// * "delta_idf": delta TF-IDF (Martineau and Finin, 2009), smoothed as by
+///   Paltoglou and Thelwall (2010): log2((P * N_t + 0.5) / (N * P_t + 0.5)).
+///   Terms evenly spread over the classes get a weight near 0, and the sign
+///   tells the class the term is biased toward (positive for the negative
+///   class).
+/// * "tf_rf": relevance frequency (Lan et al., 2009):
+///   log2(2 + P_t / max(1, N_t)). Terms mostly found in positive documents
+///   get the highest weights, and all the weights are at least 1.
+///
+/// # Examples
+/// ```
+/// extern crate vectorizer;
+///
+/// use vectorizer::countvectorizer::CountVectorizer;
+/// use vectorizer::supervisedtransformer::SupervisedTransformer;
+///
+/// let docs = vec![
+///     "great movie",
+///     "great acting",
+///     "bad movie",
+///     "bad acting",
+/// ];
+/// let labels = vec!["pos", "pos", "neg", "neg"];
+///
+/// let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+/// let counts = vectorizer.fit_transform(docs);
+///
+/// let mut transformer = SupervisedTransformer::new("tf_rf");
+/// let x = transformer.fit_transform(&counts, &labels);
+///
+/// assert_eq!((4, 4), x.dim());
+/// // "great" only appears in positive documents and "bad" in negative ones
+/// assert_eq!(vec!["great", "movie", "acting", "bad"], vectorizer.get_feature_names());
+/// assert_eq!(2.0, transformer.weights_[0]);
+/// assert_eq!(1.0, transformer.weights_[3]);
+/// ```
+///
+pub struct SupervisedTransformer<L> {
+    /// Weighting scheme. Options are "delta_idf" and "tf_rf". Panics on any
+    /// other value.
+    pub scheme: String,
+
+    /// If true, replace term frequency (tf) with 1 + ln(tf) (sublinear scaling).
+    /// Default is false.
+    pub sublinear_tf: bool,
+
+    /// Type of norm used for normalization. Options are "l1", "l2", "max",
+    /// "pivoted" and "none". Default is "l2". See pivot and slope for
+    /// "pivoted", and tfidftransformer::TfidfTransformer for details.
+    pub norm: String,
+
+    /// Pivot used by the "pivoted" norm. If None, the pivot is learned at fit
+    /// time as the average number of unique terms per document. Default is
+    /// None.
+    pub pivot: Option<f64>,
+
+    /// Slope used by the "pivoted" norm. Default is 0.2.
+    pub slope: f64,
+
+    /// Sorted labels seen by fit(): the negative class, then the positive
+    /// class.
+    pub classes_: Vec<L>,
+
+    /// Number of documents of each class containing each column (classes ×
+    /// columns).
+    pub df_: Array2<u64>,
+
+    /// Number of documents of each class.
+    pub class_count_: Array1<u64>,
+
+    /// Weight of each column, computed by fit().
+    pub weights_: Array1<f64>,
+
+    /// Pivot actually used by the "pivoted" norm, i.e. the supplied pivot or
+    /// the one learned by fit().
+    pub pivot_: f64,
+}
+
+impl<L: Clone + Ord> SupervisedTransformer<L> {
+
+    /// Create a new instance of SupervisedTransformer with sublinear_tf=false,
+    /// norm="l2", pivot=None and slope=0.2.
+    ///
+    pub fn new(scheme: &str) -> SupervisedTransformer<L> {
+        SupervisedTransformer {
+            scheme: scheme.to_string(),
+            sublinear_tf: false,
+            norm: "l2".to_string(),
+            pivot: None,
+            slope: 0.2,
+            classes_: Vec::new(),
+            df_: Array2::<u64>::zeros((0, 0)),
+            class_count_: Array1::<u64>::zeros(0),
+            weights_: Array1::<f64>::zeros(0),
+            pivot_: 0.,
+        }
+    }
+
+    fn _get_weights(&self) -> Array1<f64> {
+        let n_neg = self.class_count_[0] as f64;
+        let n_pos = self.class_count_[1] as f64;
+        let df_neg = self.df_.row(0).mapv(|e| e as f64);
+        let df_pos = self.df_.row(1).mapv(|e| e as f64);
+        match self.scheme.as_str() {
+            "delta_idf" => {
+                let numerator = df_neg * n_pos + 0.5;
+                let denominator = df_pos * n_neg + 0.5;
+                (numerator / denominator).mapv(f64::log2)
+            },
+            "tf_rf" => (df_pos / df_neg.mapv(|e| e.max(1.)) + 2.).mapv(f64::log2),
+            _ => panic!("unknown scheme: {} (options are \"delta_idf\" and \"tf_rf\")", self.scheme),
+        }
+    }
+
+    /// Learn the term weights (and the pivot of the "pivoted" norm if it is
+    /// not supplied) from a count matrix and the binary label of each row.
+    /// Panics if there are not exactly two distinct labels.
+    ///
+    pub fn fit(&mut self, countvector: &Array2<u64>, labels: &[L]) {
+        let (num_rows, num_columns) = countvector.dim();
+        assert_eq!(num_rows, labels.len(), "countvector and labels must have the same length");
+        let (classes, indices) = encode_labels(labels);
+        assert_eq!(2, classes.len(), "labels must have exactly two classes");
+
+        let mut df = Array2::<u64>::zeros((2, num_columns));
+        let mut class_count = Array1::<u64>::zeros(2);
+        for (row, &index_class) in countvector.outer_iter().zip(indices.iter()) {
+            class_count[index_class] += 1;
+            let mut class_df = df.row_mut(index_class);
+            class_df.zip_mut_with(&row, |d, &e| *d += (e != 0) as u64);
+        }
+
+        self.classes_ = classes;
+        self.df_ = df;
+        self.class_count_ = class_count;
+        self.weights_ = self._get_weights();
+
+        self.pivot_ = tfidftransformer::learned_pivot(self.pivot, self.df_.sum(), num_rows as u64);
+    }
+
+    /// Transform a count matrix into a weighted matrix using the weights
+    /// learned by fit(). The count matrix must have as many columns as the
+    /// one used for fitting.
+    ///
+    pub fn transform(&self, countvector: &Array2<u64>) -> Array2<f64> {
+        let mut x: Array2<f64> = tfidftransformer::term_frequency(countvector, self.sublinear_tf);
+        x *= &self.weights_;
+        tfidftransformer::normalize(x, self.norm.as_str(), self.pivot_, self.slope)
+    }
+
+    /// Fit to a count matrix and labels, then transform the count matrix.
+    /// See fit() and transform().
+    ///
+    pub fn fit_transform(&mut self, countvector: &Array2<u64>, labels: &[L]) -> Array2<f64> {
+        self.fit(countvector, labels);
+        self.transform(countvector)
+    }
+}
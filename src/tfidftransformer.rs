@@ -0,0 +1,327 @@
+use ndarray::{Array1, Array2, Axis};
+use num::Float;
+use ndarray_extension;
+
+// Convert a count matrix to term frequencies of type T, replacing tf with
+// 1 + ln(tf) if sublinear_tf. Zero counts stay zero.
+//
+pub(crate) fn term_frequency<T: Float>(countvector: &Array2<u64>, sublinear_tf: bool) -> Array2<T> {
+    let term_frequency = countvector.mapv(|element| T::from(element).unwrap());
+    if sublinear_tf {
+        term_frequency.mapv(|e| if e > T::zero() { e.ln() + T::one() } else { T::zero() })
+    } else {
+        term_frequency
+    }
+}
+
+// Normalize each row of a weighted matrix by norm: "l1", "l2", "max", or
+// "pivoted" with pivot and slope (see
+// ndarray_extension::pivoted_unique_normalize()). Any other norm leaves the
+// matrix unchanged. Rows without any known token are left as zeros.
+//
+pub(crate) fn normalize<T: Float>(mut x: Array2<T>, norm: &str, pivot: f64, slope: f64) -> Array2<T> {
+    match norm {
+        "l1" | "l2" | "max" => {
+            ndarray_extension::normalize_inplace(&mut x, norm, Axis(1), T::zero());
+            x
+        },
+        "pivoted" => ndarray_extension::pivoted_unique_normalize(x, pivot, slope),
+        _ => x,
+    }
+}
+
+// Pivot of "pivoted" normalization: the given pivot, or else the average
+// number of unique terms per document. The number of unique terms summed
+// over n documents equals the document frequencies summed over columns
+// (df_sum). 0 if there are no documents.
+//
+pub(crate) fn learned_pivot(pivot: Option<f64>, df_sum: u64, n: u64) -> f64 {
+    match pivot {
+        Some(pivot) => pivot,
+        None if n == 0 => 0.,
+        None => df_sum as f64 / n as f64,
+    }
+}
+
+
+/// Transforms a count matrix (e.g. the output of CountVectorizer) into a
+/// Term Frequency - Inverted Document Frequency (Tf-Idf) weighted matrix.
+/// Each row is a document and each column a token. The basic flow of the
+/// computation follows that from scikit-learn. See also
+/// tfidfvectorizer::TfidfVectorizer.
+///
+pub struct TfidfTransformer {
+    /// If true, add 1 to document frequencies to smooth idf weights, preventing
+    /// zero divisions. Default is true.
+    pub smooth_idf: bool,
+
+    /// If true, replace term frequency (tf) with 1 + ln(tf) (sublinear scaling).
+    /// Default is false.
+    pub sublinear_tf: bool,
+
+    /// Type of norm used for normalization. Options are "l1", "l2", "max",
+    /// "pivoted" and "none". Default is "l2". See pivot and slope for "pivoted".
+    pub norm: String,
+
+    /// Pivot used by the "pivoted" norm. If None, the pivot is learned at fit
+    /// time as the average number of unique terms per document. Default is
+    /// None.
+    pub pivot: Option<f64>,
+
+    /// Slope used by the "pivoted" norm. A slope of 1.0 normalizes by the
+    /// number of unique terms alone, and smaller values penalize long
+    /// documents less. Default is 0.2.
+    pub slope: f64,
+
+    /// Idf weight of each column, computed by fit().
+    pub idf_: Array1<f64>,
+
+    /// Pivot actually used by the "pivoted" norm, i.e. the supplied pivot or
+    /// the one learned by fit().
+    pub pivot_: f64,
+
+    /// Number of documents containing each column, accumulated by fit() and
+    /// partial_fit().
+    pub df_: Array1<u64>,
+
+    /// Number of documents (rows) seen by fit() and partial_fit().
+    pub n_samples_: u64,
+}
+
+impl TfidfTransformer {
+
+    /// Create a new instance of TfidfTransformer with smooth_idf=true,
+    /// sublinear_tf=false, norm="l2", pivot=None and slope=0.2.
+    ///
+    pub fn new() -> TfidfTransformer {
+        TfidfTransformer {
+            smooth_idf: true,
+            sublinear_tf: false,
+            norm: "l2".to_string(),
+            pivot: None,
+            slope: 0.2,
+            idf_: Array1::<f64>::zeros(0),
+            pivot_: 0.,
+            df_: Array1::<u64>::zeros(0),
+            n_samples_: 0,
+        }
+    }
+
+    fn _get_term_frequency<T: Float>(&self, countvector: Array2<u64>) -> Array2<T>{
+        term_frequency(&countvector, self.sublinear_tf)
+    }
+
+    fn _get_document_frequency(&self, countvector: &Array2<u64>) -> Array1<u64>{
+        // Count number of documents that contain each word
+        ndarray_extension::bincount(countvector, Axis(0))
+    }
+
+    fn _update_idf(&mut self) {
+        // Recompute idf_ and pivot_ from df_ and n_samples_
+
+        // smoothe by smooth_idf (see sklearn)
+        let smoother = (self.smooth_idf as u8) as f64;
+        let df = self.df_.mapv(|e| e as f64) + smoother;
+        let n_samples = (self.n_samples_ as f64) + smoother;
+
+        // Caclulate idf
+        let idf = n_samples / df;
+        self.idf_ = idf.mapv(f64::ln) + 1.;
+
+        self.pivot_ = learned_pivot(self.pivot, self.df_.sum(), self.n_samples_);
+    }
+
+    fn _normalize<T: Float>(&self, tfidf: Array2<T>) -> Array2<T> {
+        normalize(tfidf, self.norm.as_str(), self.pivot_, self.slope)
+    }
+
+    /// Learn the idf weights (and the pivot of the "pivoted" norm if it is
+    /// not supplied) from a count matrix. Any previously accumulated
+    /// statistics are discarded. See also partial_fit().
+    ///
+    pub fn fit(&mut self, countvector: &Array2<u64>) {
+        self.df_ = Array1::<u64>::zeros(0);
+        self.n_samples_ = 0;
+        self.partial_fit(countvector);
+    }
+
+    /// Accumulate the document frequencies and the number of documents of a
+    /// batch of counts, and recompute the idf weights (and the learned
+    /// pivot). The batch may have more columns than the previous ones (e.g.
+    /// when the vocabulary was extended), in which case the new columns
+    /// start with a document frequency of zero. Calling partial_fit() on
+    /// consecutive batches gives the same idf weights as fit() on the
+    /// stacked batches.
+    ///
+    pub fn partial_fit(&mut self, countvector: &Array2<u64>) {
+        let (num_rows, num_columns) = countvector.dim();
+
+        // Extend document frequencies for new columns
+        let num_known_columns = self.df_.len();
+        if num_columns > num_known_columns {
+            let mut df = Array1::<u64>::zeros(num_columns);
+            df.slice_mut(s![..num_known_columns]).assign(&self.df_);
+            self.df_ = df;
+        }
+
+        let df_batch = self._get_document_frequency(countvector);
+        let mut df_known = self.df_.slice_mut(s![..num_columns]);
+        df_known += &df_batch;
+        self.n_samples_ += num_rows as u64;
+        self._update_idf();
+    }
+
+    /// Transform a count matrix into a Tf-Idf matrix using the idf weights
+    /// learned by fit(). The count matrix must have as many columns as the
+    /// one used for fitting.
+    ///
+    pub fn transform(&self, countvector: &Array2<u64>) -> Array2<f64> {
+        self.transform_as(countvector)
+    }
+
+    /// Same as transform(), but the weights are computed and returned with
+    /// the float type T (f32 or f64).
+    ///
+    pub fn transform_as<T: Float>(&self, countvector: &Array2<u64>) -> Array2<T> {
+        let tf = self._get_term_frequency::<T>(countvector.clone());
+        let num_columns = self.idf_.len();
+        let idf = self.idf_.mapv(|e| T::from(e).unwrap()).into_shape((1, num_columns)).unwrap();
+        self._normalize(tf * &idf)
+    }
+
+    /// Fit to a count matrix, then transform it. See fit() and transform().
+    ///
+    /// # Examples
+    /// ```
+    /// extern crate ndarray;
+    /// extern crate vectorizer;
+    ///
+    /// use ndarray::arr2;
+    /// use vectorizer::tfidftransformer::TfidfTransformer;
+    ///
+    /// let counts = arr2(&[
+    ///     [3, 0, 1],
+    ///     [2, 0, 0],
+    ///     [3, 0, 0],
+    ///     [4, 0, 0],
+    ///     [3, 2, 0],
+    ///     [3, 0, 2]]);
+    ///
+    /// // Pivoted unique normalization with a pivot learned from the counts
+    /// let mut transformer = TfidfTransformer::new();
+    /// transformer.norm = "pivoted".to_string();
+    /// let tfidf = transformer.fit_transform(&counts);
+    ///
+    /// assert_eq!((6, 3), tfidf.dim());
+    /// assert_eq!(1.5, transformer.pivot_);
+    /// println!("Tf-Idf Matrix:\n{:?}", tfidf);
+    /// ```
+    ///
+    pub fn fit_transform(&mut self, countvector: &Array2<u64>) -> Array2<f64> {
+        self.fit_transform_as(countvector)
+    }
+
+    /// Same as fit_transform(), but the weights are computed and returned
+    /// with the float type T (f32 or f64).
+    ///
+    pub fn fit_transform_as<T: Float>(&mut self, countvector: &Array2<u64>) -> Array2<T> {
+        self.fit(countvector);
+        self.transform_as(countvector)
+    }
+}
+
+impl Default for TfidfTransformer {
+    fn default() -> TfidfTransformer {
+        TfidfTransformer::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[ignore]
+    fn test_internal_methods() {
+        let x = array![
+            [1, 0, 0],
+            [0, 1, 0],
+            [0, 0, 1],
+            [1, 0, 0]
+        ];
+        let y = array![
+            [1, 2, 3],
+            [4, 5, 6],
+            [7, 8, 9],
+            [10, 11, 12]
+        ];
+
+        let transformer1 = TfidfTransformer::new();
+        let transformer2 = TfidfTransformer::new();
+
+        // test _get_term_frequency()
+        let tf1 = transformer1._get_term_frequency::<f64>(x.clone());
+        let tf2 = transformer2._get_term_frequency::<f64>(y.clone());
+
+        let ans_tf1 = array![
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 0.0, 0.0]];
+        let ans_tf2 = array![
+            [1.0, 2.0, 3.0],
+            [4.0, 5.0, 6.0],
+            [7.0, 8.0, 9.0],
+            [10.0, 11.0, 12.0]];
+
+        assert_eq!(ans_tf1, tf1);
+        assert_eq!(ans_tf2, tf2);
+
+        // test _get_document_frequency()
+        let df1 = transformer1._get_document_frequency(&x);
+        let df2 = transformer2._get_document_frequency(&y);
+
+        let ans_df1 = array![2, 1, 1];
+        let ans_df2 = array![4, 4, 4];
+
+        assert_eq!(ans_df1, df1);
+        assert_eq!(ans_df2, df2);
+
+        // print results
+        println!("=== Testing Term Frequency ===");
+        println!("X TF:\n{:?}", tf1);
+        println!("Y TF:\n{:?}", tf2);
+        println!("\n");
+
+        println!("=== Testing Document Frequency ===");
+        println!("X DF:\n{:?}", df1);
+        println!("Y DF:\n{:?}", df2);
+        println!("\n");
+    }
+
+    #[test]
+    #[ignore]
+    fn test_tfidf_transform(){
+        let x = array![
+            [1, 0, 0],
+            [0, 1, 0],
+            [0, 0, 1],
+            [2, 0, 0]
+        ];
+        let y = array![
+            [1, 2, 3],           [4, 5, 6],
+            [7, 8, 9],
+            [10, 11, 12]
+        ];
+
+        let mut transformer1 = TfidfTransformer::new();
+        let mut transformer2 = TfidfTransformer::new();
+
+        let tfidf1 = transformer1.fit_transform(&x);
+        let tfidf2 = transformer2.fit_transform(&y);
+        println!("X tf-idf:\n{:?}", tfidf1);
+        println!("Y tf-idf:\n{:?}", tfidf2);
+
+    }
+}
@@ -1,7 +1,11 @@
 use std::collections::HashMap;
-use ndarray::{Array1, Array2};
+use std::mem;
+use ndarray::{Array1, Array2, Axis};
+use num::{Float, Zero};
 use ndarray_extension;
-use countvectorizer::CountVectorizer;
+use countvectorizer::{self, CountVectorizer};
+use tfidftransformer::TfidfTransformer;
+use tokenizer::Tokenizer;
 
 /// Transforms a collection of documents (i.e. Vec of string slices) using
 /// Term Frequency - Inverted Document Frequency (Tf-Idf) transformation. 
@@ -31,17 +35,40 @@ pub struct TfidfVectorizer {
     /// Default is false.
     pub sublinear_tf: bool,
 
-    /// Type of norm used for normalization. Options are "l1", "l2", "none".
-    /// Default is "l2"
+    /// Type of norm used for normalization. Options are "l1", "l2", "max",
+    /// "pivoted" and "none". Default is "l2". See also tfidftransformer::TfidfTransformer
+    /// for details.
     pub norm: String,
+
+    /// Pivot used by the "pivoted" norm. If None, the pivot is learned at fit
+    /// time as the average number of unique terms per document. Default is
+    /// None.
+    pub pivot: Option<f64>,
+
+    /// Slope used by the "pivoted" norm. Default is 0.2.
+    pub slope: f64,
+
+    /// Idf weight of each token, indexed by the IDs in vocabulary_.
+    pub idf_: Array1<f64>,
+
+    /// Pivot actually used by the "pivoted" norm, i.e. the supplied pivot or
+    /// the one learned at fit time.
+    pub pivot_: f64,
+
+    /// Number of documents containing each token, indexed by the IDs in
+    /// vocabulary_. Accumulated by partial_fit().
+    pub df_: Array1<u64>,
+
+    /// Number of documents seen at fit time. Accumulated by partial_fit().
+    pub n_samples_: u64,
 }
 
 impl TfidfVectorizer {
  
     /// Create a new instance of TfidfVectorizer. Initialized with an empty
     /// vocabulary map (HashMap<String, u64> type), smooth_idf=true, sublinear
-    /// _tf=false, and norm="l2" Currently only implments those default 
-    /// parameters. Other options to be implemented.
+    /// _tf=false, norm="l2", pivot=None and slope=0.2. Options can be changed
+    /// via the public fields before fitting.
     /// 
     pub fn new(ngram_range : (u32, u32), case: &str) -> TfidfVectorizer {
         let map: HashMap<String, u64> = HashMap::new();
@@ -49,11 +76,17 @@ impl TfidfVectorizer {
         // Return a new instance
         TfidfVectorizer {
             vocabulary_: map,
-            ngram_range : ngram_range,
+            ngram_range,
             case: case.to_string(),
             smooth_idf: true,
             sublinear_tf: false,
             norm: "l2".to_string(),
+            pivot: None,
+            slope: 0.2,
+            idf_: Array1::<f64>::zeros(0),
+            pivot_: 0.,
+            df_: Array1::<u64>::zeros(0),
+            n_samples_: 0,
         }
     }
 
@@ -65,51 +98,210 @@ impl TfidfVectorizer {
         countvector
     }
 
-    fn _get_term_frequency(&self, countvector: Array2<u64>) -> Array2<f64>{
-        // Convert to f64 with or without sublinear adjustment
+    fn _create_transformer(&self) -> TfidfTransformer {
+        // TfidfTransformer with the same settings and fitted state as this
+        // vectorizer
+        let mut transformer = TfidfTransformer::new();
+        transformer.smooth_idf = self.smooth_idf;
+        transformer.sublinear_tf = self.sublinear_tf;
+        transformer.norm = self.norm.clone();
+        transformer.pivot = self.pivot;
+        transformer.slope = self.slope;
+        transformer.idf_ = self.idf_.clone();
+        transformer.pivot_ = self.pivot_;
+        transformer.df_ = self.df_.clone();
+        transformer.n_samples_ = self.n_samples_;
+        transformer
+    }
 
-        let term_frequency = countvector.mapv(|element| element as f64);
+    fn _store_fitted_transformer(&mut self, transformer: TfidfTransformer) {
+        // Keep the fitted state of a TfidfTransformer
+        self.idf_ = transformer.idf_;
+        self.pivot_ = transformer.pivot_;
+        self.df_ = transformer.df_;
+        self.n_samples_ = transformer.n_samples_;
+    }
 
-        // if sublinear_tf, pre-process countvector
-        if self.sublinear_tf {
-            term_frequency.mapv(f64::ln) + 1.0  // addition of 1 per sklearn
-        } else { 
-            term_frequency
-        }
+    fn _tfidi_transform<T: Float>(&mut self, countvector: Array2<u64>) -> Array2<T> {
+        // Convert CountVector to Tf-Idf Vector by TfidfTransformer
+        let mut transformer = self._create_transformer();
+        let tfidf = transformer.fit_transform_as(&countvector);
+        self._store_fitted_transformer(transformer);
+        tfidf
+    }
+
+    /// Learn the vocabulary and idf weights from the collection of
+    /// documents. Any previously learned state is discarded. See also
+    /// partial_fit().
+    /// 
+    pub fn fit(&mut self, docs: Vec<&str>) {
+        self.vocabulary_.clear();
+        self.df_ = Array1::<u64>::zeros(0);
+        self.n_samples_ = 0;
+        self.partial_fit(docs);
+    }
+
+    /// Extend the vocabulary with a batch of documents and accumulate the
+    /// document frequencies (df_) and the number of documents (n_samples_),
+    /// then recompute idf_. Calling partial_fit() on consecutive batches
+    /// gives the same vocabulary, idf weights and transform() results as
+    /// fit() on the concatenated batches.
+    /// 
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    /// 
+    /// use vectorizer::tfidfvectorizer::TfidfVectorizer;
+    /// 
+    /// let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    /// vectorizer.partial_fit(vec!["apple banana", "banana"]);
+    /// vectorizer.partial_fit(vec!["orange apple"]);
+    /// 
+    /// let mut batch_vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    /// batch_vectorizer.fit(vec!["apple banana", "banana", "orange apple"]);
+    /// 
+    /// assert_eq!(3, vectorizer.n_samples_);
+    /// assert_eq!(batch_vectorizer.idf_, vectorizer.idf_);
+    /// ```
+    /// 
+    pub fn partial_fit(&mut self, docs: Vec<&str>) {
+        // Extend the vocabulary by CountVectorizer and count the batch
+        let mut count_vectorizer = CountVectorizer::new(self.ngram_range, self.case.as_str());
+        count_vectorizer.vocabulary_ = mem::take(&mut self.vocabulary_);
+        let countvector = count_vectorizer.partial_fit_transform(docs);
+        self.vocabulary_ = count_vectorizer.vocabulary_;
+
+        // Accumulate document frequencies by TfidfTransformer
+        let mut transformer = self._create_transformer();
+        transformer.partial_fit(&countvector);
+        self._store_fitted_transformer(transformer);
     }
 
-    fn _get_document_frequency(&self, countvector: Array2<u64>) -> Array1<f64>{
-        // Count number of documents that contain each word
-        // *implementation is probably inefficient. It's a first-pass
-        ndarray_extension::bincount(countvector)
+    /// Tf-Idf transform the collection of documents with the vocabulary and
+    /// idf weights learned at fit time. Tokens not in the vocabulary are
+    /// ignored.
+    /// 
+    pub fn transform(&self, docs: Vec<&str>) -> Array2<f64> {
+        self.transform_as(docs)
+    }
+
+    // Tf-Idf transform a count matrix over vocabulary_ (e.g. from
+    // countvectorizer::count_tokens()), for callers that already tokenized
+    // the documents
+    //
+    pub(crate) fn transform_counts(&self, countvector: &Array2<u64>) -> Array2<f64> {
+        self._create_transformer().transform(countvector)
+    }
 
+    /// Same as transform(), but the weights are computed and returned with
+    /// the float type T (f32 or f64).
+    /// 
+    pub fn transform_as<T: Float>(&self, docs: Vec<&str>) -> Array2<T> {
+        let tk = Tokenizer::new(self.ngram_range, self.case.as_str());
+        let tokenized_docs = tk.tokenize(docs);
+        // u64 counts cannot overflow
+        let countvector = countvectorizer::count_tokens(&self.vocabulary_, tokenized_docs).unwrap();
+        self._create_transformer().transform_as(&countvector)
     }
 
-    fn _get_idf_matrix(&self, countvector: Array2<u64>) -> Array2<f64>{
-        // create idf matrix to multiply tf matrix with
-
-        // get countvector dimension and get document frequency vector
-        let (num_rows, _) = countvector.dim();
-        let mut df = self._get_document_frequency(countvector);
-
-        // smoothe by smooth_idf (see sklearn)
-        let smoother = (self.smooth_idf as u8) as f64;
-        df = df + smoother;
-        let n_samples = (num_rows as f64) + smoother;
-    
-        // Caclulate idf and convert to diagonal matrix
-        let mut idf = n_samples / df;
-        idf = idf.mapv(f64::ln) + 1.;
-        ndarray_extension::vec2diagonal(idf)
+    /// Tokens of the vocabulary ordered by token ID, i.e. the token of each
+    /// column of the Tf-Idf matrix.
+    /// 
+    pub fn get_feature_names(&self) -> Vec<String> {
+        countvectorizer::feature_names(&self.vocabulary_)
+    }
+
+    /// Keep only the vocabulary columns in support (e.g. the columns chosen
+    /// by featureselection::SelectKBest), together with their idf weights and
+    /// document frequencies, so that transform() yields only those columns.
+    /// The learned pivot of the "pivoted" norm is kept as is. Panics if a
+    /// column is not in the vocabulary. See also
+    /// CountVectorizer::restrict_vocabulary().
+    /// 
+    pub fn restrict_vocabulary(&mut self, support: &[usize]) {
+        let mut support = support.to_vec();
+        support.sort_unstable();
+        support.dedup();
+        self.vocabulary_ = countvectorizer::restrict_vocabulary(&self.vocabulary_, &support);
+        if !self.idf_.is_empty() {
+            self.idf_ = self.idf_.select(Axis(0), &support);
+            self.df_ = self.df_.select(Axis(0), &support);
+        }
+    }
+
+    /// Map each row of a Tf-Idf matrix back to the tokens with non-zero
+    /// weights, in order of token ID.
+    /// 
+    pub fn inverse_transform<T: Clone + Zero>(&self, matrix: &Array2<T>) -> Vec<Vec<String>> {
+        countvectorizer::inverse_transform(&self.get_feature_names(), matrix)
+    }
+
+    /// Same as inverse_transform(), but each token comes with its weight and
+    /// tokens are sorted by weight in descending order.
+    /// 
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    /// 
+    /// use vectorizer::tfidfvectorizer::TfidfVectorizer;
+    /// 
+    /// let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    /// let x = vectorizer.fit_transform(vec!["apple banana banana", "apple"]);
+    /// 
+    /// let weights = vectorizer.inverse_transform_with_weights(&x);
+    /// assert_eq!("banana", weights[0][0].0);
+    /// assert_eq!("apple", weights[0][1].0);
+    /// assert!(weights[0][0].1 > weights[0][1].1);
+    /// ```
+    /// 
+    pub fn inverse_transform_with_weights<T: Clone + Zero + PartialOrd>(&self, matrix: &Array2<T>) -> Vec<Vec<(String, T)>> {
+        countvectorizer::inverse_transform_with_weights(&self.get_feature_names(), matrix)
     }
 
-    fn _tfidi_transform(&self, countvector: Array2<u64>) -> Array2<f64> {
-        // Convert CountVector to Tf-Idf Vector
+    /// Top-k highest weighted tokens of each row of a Tf-Idf matrix with
+    /// their weights, sorted by weight in descending order. Rows with fewer
+    /// than k non-zero weights return all of them. Tokens are selected by
+    /// partial selection rather than sorting whole rows.
+    /// 
+    pub fn top_k_keywords(&self, matrix: &Array2<f64>, k: usize) -> Vec<Vec<(String, f64)>> {
+        assert_eq!(self.vocabulary_.len(), matrix.ncols(), "number of columns differs from vocabulary size");
+        let feature_names = self.get_feature_names();
+        matrix.outer_iter()
+            .map(|row| {
+                let candidates: Vec<(usize, f64)> = row.iter()
+                    .enumerate()
+                    .filter(|&(_, e)| *e != 0.)
+                    .map(|(index_col, e)| (index_col, *e))
+                    .collect();
+                ndarray_extension::top_k(candidates, k).into_iter()
+                    .map(|(index_col, weight)| (feature_names[index_col].clone(), weight))
+                    .collect()
+            })
+            .collect()
+    }
 
-        let tf = self._get_term_frequency(countvector.clone());
-        let idf = self._get_idf_matrix(countvector);
-        let tfidf = tf.dot(&idf);
-        ndarray_extension::l2_normalize(tfidf)  // l2 normalize. To be refactored
+    /// Transform the collection of documents and return the top-k highest
+    /// weighted tokens of each document. See also top_k_keywords().
+    /// 
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    /// 
+    /// use vectorizer::tfidfvectorizer::TfidfVectorizer;
+    /// 
+    /// let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    /// vectorizer.fit(vec![
+    ///     "rust compiler borrow checker",
+    ///     "python interpreter",
+    ///     "rust python bindings"]);
+    /// 
+    /// let keywords = vectorizer.extract_keywords(vec!["borrow checker in rust"], 2);
+    /// let tokens: Vec<&str> = keywords[0].iter().map(|(t, _)| t.as_str()).collect();
+    /// assert_eq!(vec!["borrow", "checker"], tokens);
+    /// ```
+    /// 
+    pub fn extract_keywords(&self, docs: Vec<&str>, k: usize) -> Vec<Vec<(String, f64)>> {
+        self.top_k_keywords(&self.transform(docs), k)
     }
 
     /// Fit and tfidf transform the collection of documents. It returns
@@ -182,9 +374,26 @@ impl TfidfVectorizer {
     /// 
     pub fn fit_transform(&mut self, docs: Vec<&str>) -> Array2<f64> {
         // Public API for transformation
+        self.fit_transform_as(docs)
+    }
+
+    /// Same as fit_transform(), but the weights are computed and returned
+    /// with the float type T (f32 or f64).
+    /// 
+    /// # Examples
+    /// ```
+    /// extern crate vectorizer;
+    /// 
+    /// use vectorizer::tfidfvectorizer::TfidfVectorizer;
+    /// 
+    /// let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    /// let x = vectorizer.fit_transform_as::<f32>(vec!["apple banana", "banana"]);
+    /// assert_eq!(1f32, x[[1, 1]]);
+    /// ```
+    /// 
+    pub fn fit_transform_as<T: Float>(&mut self, docs: Vec<&str>) -> Array2<T> {
         let countvector = self._create_countvector(docs);
-        let tfidfvector = self._tfidi_transform(countvector);
-        tfidfvector
+        self._tfidi_transform(countvector)
     }
 }
 
@@ -195,6 +404,7 @@ mod tests {
 
     #[test]
     #[ignore]
+    #[allow(clippy::vec_init_then_push)]
     fn test_create_countvector(){
         let fruits_str = "apple, banana, apple, banana, orange, three, \
                         apple. apple, banana, orange, orange, ONE, three";
@@ -221,88 +431,4 @@ mod tests {
         println!("CountVector :\n{:?}", countvector);
         println!("\n");
     }
-
-    #[test]
-    #[ignore]
-    fn test_internal_methods() {
-        let x = array![
-            [1, 0, 0],
-            [0, 1, 0],
-            [0, 0, 1],
-            [1, 0, 0]
-        ];
-        let y = array![
-            [1, 2, 3],
-            [4, 5, 6],
-            [7, 8, 9],
-            [10, 11, 12]
-        ];
-
-        let vectorizer1 = TfidfVectorizer::new((1, 2), "lower");
-        let vectorizer2 = TfidfVectorizer::new((1, 2), "lower");
-
-        // test _get_term_frequency()
-        let tf1 = vectorizer1._get_term_frequency(x.clone());
-        let tf2 = vectorizer2._get_term_frequency(y.clone());
-
-        let ans_tf1 = array![
-            [1.0, 0.0, 0.0],
-            [0.0, 1.0, 0.0],
-            [0.0, 0.0, 1.0],
-            [1.0, 0.0, 0.0]];
-        let ans_tf2 = array![
-            [1.0, 2.0, 3.0],
-            [4.0, 5.0, 6.0],
-            [7.0, 8.0, 9.0],
-            [10.0, 11.0, 12.0]];
-
-        assert_eq!(ans_tf1, tf1);
-        assert_eq!(ans_tf2, tf2);
-
-        // test _get_document_frequency()
-        let df1 = vectorizer1._get_document_frequency(x);
-        let df2 = vectorizer2._get_document_frequency(y);
-
-        let ans_df1 = array![2., 1., 1.];
-        let ans_df2 = array![4., 4., 4.];
-
-        assert_eq!(ans_df1, df1);
-        assert_eq!(ans_df2, df2);
-
-        // print results
-        println!("=== Testing Term Frequency ===");
-        println!("X TF:\n{:?}", tf1);
-        println!("Y TF:\n{:?}", tf2);
-        println!("\n");
-
-        println!("=== Testing Document Frequency ===");
-        println!("X DF:\n{:?}", df1);
-        println!("Y DF:\n{:?}", df2);
-        println!("\n");
-    }
-
-    #[test]
-    #[ignore]
-    fn test_tfidf_transform(){
-        let x = array![
-            [1, 0, 0],
-            [0, 1, 0],
-            [0, 0, 1],
-            [2, 0, 0]
-        ];
-        let y = array![
-            [1, 2, 3],           [4, 5, 6],
-            [7, 8, 9],
-            [10, 11, 12]
-        ];
-
-        let vectorizer1 = TfidfVectorizer::new((1, 2), "lower");
-        let vectorizer2 = TfidfVectorizer::new((1, 2), "lower");
-
-        let tfidf1 = vectorizer1._tfidi_transform(x);
-        let tfidf2 = vectorizer2._tfidi_transform(y);
-        println!("X tf-idf:\n{:?}", tfidf1);
-        println!("Y tf-idf:\n{:?}", tfidf2);
-
-    }
 }
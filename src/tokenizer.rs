@@ -1,6 +1,6 @@
 //! 
 //! Current issues:
-//! - Only tokenize into words and/or n-grams of words
+//! - Only tokenize into n-grams of words or of characters
 //! - Accents, etc are not handled
 
 use std::vec::Vec;
@@ -8,6 +8,8 @@ use std::cmp;
 use std::ops::Range;
 use std::string::String;
 use regex::Regex;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 
 /// A struct used to tokenize a collection of documents (i.e. Vector of
@@ -24,10 +26,18 @@ pub struct Tokenizer {
     /// The case of the resulting tokens. Default is no conversion. Options 
     /// are "upper" and "lower". Other inputs will use default. 
     pub case: String,
+
+    /// Whether tokens are n-grams of words ("word") or of characters
+    /// ("char"), e.g. for shingles of minhash::MinHash. Character n-grams
+    /// are taken over the whole document with runs of whitespace collapsed
+    /// into a single space. Default is "word", which is also used for other
+    /// inputs.
+    pub analyzer: String,
 }
 
 impl Tokenizer{
-    /// Create a new instance of Tokenizer with ngram_range ((u32, u32)) input.
+    /// Create a new instance of Tokenizer with ngram_range ((u32, u32)) input
+    /// and analyzer="word".
     pub fn new(ngram_range: (u32, u32), case: &str) -> Tokenizer {
         // Check the specifications
         let (min_n, max_n) = ngram_range;
@@ -37,12 +47,13 @@ impl Tokenizer{
         } else if min_n > max_n {
             println!("WARNING: Lower bound of of ngram_range \
                 larger than upper bound. Empty tokens will be returned.")
-        } else {}
+        }
 
         // Return tokenizer
         Tokenizer {
-            ngram_range: ngram_range,
+            ngram_range,
             case: case.to_string(),
+            analyzer: "word".to_string(),
         }
     }
     
@@ -67,18 +78,42 @@ impl Tokenizer{
         final_tokens
     }
 
-    // tokenize a single doc (i.e. &str) by regex followed by _word_ngrams().
-    // It returns Vec<String>
-    fn _tokenize_single_doc<'a>(&self, doc: &'a str) -> Vec<String> {
-        // Split into words/tokens
-        let token_pattern=r"(?u)\b\w\w+\b";
-        let _re = Regex::new(token_pattern).unwrap();
-        let _tokens: Vec<&str> = _re.find_iter(doc)
-            .map(|f| f.as_str())
+    // Takes a doc and returns its character N-grams for 'N's specified by
+    // ngram_range, after collapsing runs of whitespace into a single space.
+    //
+    fn _char_ngrams(&self, doc: &str) -> Vec<String> {
+        let (min_n, max_n) = self.ngram_range;
+        let chars: Vec<char> = doc.split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" ")
+            .chars()
             .collect();
+        let num_chars = chars.len() as u32;
+        let mut final_tokens = <Vec<String>>::new();
+        for n in min_n.max(1)..cmp::min(max_n + 1, num_chars + 1) {
+            for i in 0..(num_chars - n + 1) {
+                final_tokens.push(chars[i as usize..(i + n) as usize].iter().collect());
+            }
+        }
+        final_tokens
+    }
+
+    // tokenize a single doc (i.e. &str) by regex followed by _word_ngrams(),
+    // or by _char_ngrams(). It returns Vec<String>
+    fn _tokenize_single_doc(&self, doc: &str) -> Vec<String> {
+        let mut _ngrams_tokens: Vec<_> = if self.analyzer == "char" {
+            self._char_ngrams(doc)
+        } else {
+            // Split into words/tokens
+            let token_pattern=r"(?u)\b\w\w+\b";
+            let _re = Regex::new(token_pattern).unwrap();
+            let _tokens: Vec<&str> = _re.find_iter(doc)
+                .map(|f| f.as_str())
+                .collect();
+            self._word_ngrams(_tokens)
+        };
 
         // Case conversion
-        let mut _ngrams_tokens: Vec<_> = self._word_ngrams(_tokens);
         match self.case.as_str() {  // could refactor to return conversion function
             "upper" => {
                 _ngrams_tokens = _ngrams_tokens.iter()
@@ -99,7 +134,8 @@ impl Tokenizer{
 
     /// It takes a collection of documents (i.e. Vec<&str>), tokenize each 
     /// doc with the Tokenizer's specs, and collect returned Vec<String> 
-    /// into a Vec (Vec<Vec<String>> returned.
+    /// into a Vec (Vec<Vec<String>> returned. With the "parallel" feature,
+    /// documents are tokenized in parallel and the order is preserved.
     ///
     /// # Examples
     /// ```
@@ -129,7 +165,12 @@ impl Tokenizer{
     /// println!("Uni~Tri-gram ({:?}): {:?}\n", tk3.case, tokens3);
     /// ```
     /// 
-    pub fn tokenize<'a>(&self, docs: Vec<&'a str>) -> Vec<Vec<String>> {
+    pub fn tokenize(&self, docs: Vec<&str>) -> Vec<Vec<String>> {
+        self._tokenize_docs(docs)
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn _tokenize_docs(&self, docs: Vec<&str>) -> Vec<Vec<String>> {
         let mut _tokenized_docs: Vec<Vec<String>> = Vec::new();
         for doc in docs {
             let mut _tokens: Vec<String> ;
@@ -138,4 +179,11 @@ impl Tokenizer{
         };
         _tokenized_docs
     }
+
+    #[cfg(feature = "parallel")]
+    fn _tokenize_docs(&self, docs: Vec<&str>) -> Vec<Vec<String>> {
+        docs.par_iter()
+            .map(|doc| self._tokenize_single_doc(doc))
+            .collect()
+    }
 }
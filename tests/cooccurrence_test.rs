@@ -0,0 +1,108 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::cooccurrence::CooccurrenceVectorizer;
+
+#[test]
+fn test_cooccurrence(){
+    let mut vectorizer = CooccurrenceVectorizer::new(2, "lower");
+    vectorizer.fit(vec!["aa bb cc", "AA cc"]);
+
+    assert_eq!(vec!["aa", "bb", "cc"], vectorizer.get_feature_names());
+    assert_eq!(array![2, 1, 2], vectorizer.word_counts_);
+
+    // aa-bb at distance 1, aa-cc at distances 2 and 1, bb-cc at distance 1
+    assert_eq!(array![
+        [0.0, 1.0, 1.5],
+        [1.0, 0.0, 1.0],
+        [1.5, 1.0, 0.0]], vectorizer.cooccurrence_.to_dense());
+}
+
+#[test]
+fn test_weighting(){
+    let docs = vec!["aa bb cc dd"];
+    let mut vectorizer = CooccurrenceVectorizer::new(3, "none");
+
+    vectorizer.weighting = "linear".to_string();
+    vectorizer.fit(docs.clone());
+    assert_eq!(array![0.0, 1.0, 2. / 3., 1. / 3.], vectorizer.cooccurrence_.to_dense().row(0));
+
+    vectorizer.weighting = "none".to_string();
+    vectorizer.window = 2;
+    vectorizer.fit(docs);
+    assert_eq!(array![0.0, 1.0, 1.0, 0.0], vectorizer.cooccurrence_.to_dense().row(0));
+}
+
+#[test]
+fn test_min_count(){
+    let mut vectorizer = CooccurrenceVectorizer::new(1, "lower");
+    vectorizer.min_count = 2;
+    vectorizer.weighting = "none".to_string();
+    vectorizer.fit(vec!["aa rare bb", "aa bb"]);
+
+    // Rare words are removed before windowing, so aa and bb are adjacent
+    assert_eq!(vec!["aa", "bb"], vectorizer.get_feature_names());
+    assert_eq!(array![[0.0, 2.0], [2.0, 0.0]], vectorizer.cooccurrence_.to_dense());
+}
+
+#[test]
+fn test_ppmi(){
+    let mut vectorizer = CooccurrenceVectorizer::new(1, "lower");
+    vectorizer.context_smoothing = 1.0;
+    let vectors = vectorizer.fit_transform(vec!["aa bb", "aa cc", "aa bb"]);
+
+    // Counts: aa-bb 2, aa-cc 1; total 6, sums aa 3, bb 2, cc 1
+    let cooccurrence = vectorizer.cooccurrence_.to_dense();
+    let total = cooccurrence.sum();
+    let sums = cooccurrence.sum_axis(ndarray::Axis(1));
+    let ppmi = vectorizer.ppmi_.to_dense();
+    for i in 0..3 {
+        for j in 0..3 {
+            let expected = if cooccurrence[[i, j]] > 0. {
+                (cooccurrence[[i, j]] * total / (sums[i] * sums[j])).ln().max(0.)
+            } else {
+                0.
+            };
+            assert!((ppmi[[i, j]] - expected).abs() < 1e-12);
+        }
+    }
+    assert!(ppmi.iter().all(|&e| e >= 0.));
+    assert_eq!(ppmi, vectors);
+}
+
+#[test]
+fn test_svd_vectors(){
+    let docs = vec![
+        "the cat drinks milk every morning",
+        "the dog drinks water every morning",
+        "a cat chases a dog",
+        "the kitten drinks milk",
+        "stocks fell as the market closed",
+        "the market rallied and stocks rose",
+        "bond prices fell as the market closed",
+    ];
+    let mut vectorizer = CooccurrenceVectorizer::new(2, "lower");
+    vectorizer.n_components = Some(4);
+    let vectors = vectorizer.fit_transform(docs);
+
+    let num_words = vectorizer.vocabulary_.len();
+    assert_eq!((num_words, 4), vectors.dim());
+    assert_eq!(vectors.row(0), vectorizer.get_vector("the").unwrap());
+    assert!(vectorizer.get_vector("unknown").is_none());
+
+    // Neighbors exclude the word itself and are sorted by similarity
+    let similar = vectorizer.most_similar("cat", 3);
+    assert_eq!(3, similar.len());
+    assert!(similar.iter().all(|(word, _)| word != "cat"));
+    assert!(similar.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+    assert!(vectorizer.most_similar("unknown", 3).is_empty());
+}
+
+#[test]
+#[should_panic]
+fn test_unknown_weighting(){
+    let mut vectorizer = CooccurrenceVectorizer::new(2, "lower");
+    vectorizer.weighting = "gaussian".to_string();
+    vectorizer.fit(vec!["aa bb"]);
+}
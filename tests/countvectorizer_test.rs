@@ -1,6 +1,11 @@
+// The original tests are kept as written
+#![allow(clippy::vec_init_then_push, clippy::unnecessary_cast)]
+
 extern crate vectorizer;
 
+use std::collections::HashMap;
 use vectorizer::countvectorizer::CountVectorizer;
+use vectorizer::tokenizer::Tokenizer;
 
 #[test]
 #[ignore]
@@ -130,4 +135,117 @@ fn example3() {
 
     println!("CountVector :\n{:?}", x3);
     println!("\n");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_partial_fit_matches_fit() {
+    let batch1 = vec!["apple banana apple", "banana orange"];
+    let batch2 = vec!["grape apple", "melon banana grape"];
+    let docs = vec!["apple banana apple", "banana orange", "grape apple", "melon banana grape"];
+
+    let mut vectorizer = CountVectorizer::new((1, 2), "lower");
+    vectorizer.fit(docs.clone());
+
+    let mut incremental = CountVectorizer::new((1, 2), "lower");
+    incremental.partial_fit(batch1);
+    let vocabulary_size = incremental.vocabulary_.len();
+    incremental.partial_fit(batch2);
+
+    // Existing IDs are kept and new tokens are appended
+    assert!(incremental.vocabulary_.len() > vocabulary_size);
+    assert_eq!(vectorizer.vocabulary_, incremental.vocabulary_);
+    assert_eq!(vectorizer.transform(docs.clone()), incremental.transform(docs));
+}
+
+#[test]
+fn test_transform_ignores_unknown_tokens() {
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    let x_fit = vectorizer.fit_transform(vec!["apple banana apple", "banana"]);
+    let x = vectorizer.transform(vec!["apple banana apple", "kiwi apple"]);
+
+    assert_eq!((2, 2), x.dim());
+    assert_eq!(x_fit.row(0), x.row(0));
+    assert_eq!(1, x[[1, vectorizer.vocabulary_["apple"] as usize]]);
+    assert_eq!(0, x[[1, vectorizer.vocabulary_["banana"] as usize]]);
+}
+
+#[test]
+fn test_vocabulary_in_order_of_appearance() {
+    // Enough documents to be split into several chunks by the "parallel"
+    // feature; the result must not depend on it.
+    let words = ["apple", "banana", "orange", "grape", "melon", "kiwi", "lemon", "peach"];
+    let docs_string: Vec<String> = (0..200)
+        .map(|i| {
+            (0..(i % 5 + 1))
+                .map(|j| format!("{}{}", words[(i * 3 + j) % words.len()], (i * j) % 37))
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect();
+    let docs: Vec<&str> = docs_string.iter().map(|s| s.as_str()).collect();
+
+    let mut vectorizer = CountVectorizer::new((1, 2), "lower");
+    vectorizer.partial_fit(vec!["kiwi0 apple0"]);
+    let x = vectorizer.fit_transform(docs.clone());
+
+    // Expected IDs and counts, computed sequentially
+    let tokenized_docs = Tokenizer::new((1, 2), "lower").tokenize(docs);
+    let mut expected_vocabulary: HashMap<String, u64> = HashMap::new();
+    for doc in tokenized_docs.iter() {
+        for token in doc {
+            let next_index = expected_vocabulary.len() as u64;
+            expected_vocabulary.entry(token.clone()).or_insert(next_index);
+        }
+    }
+    assert_eq!(expected_vocabulary, vectorizer.vocabulary_);
+    for (index_row, doc) in tokenized_docs.iter().enumerate() {
+        assert_eq!(doc.len() as u64, x.row(index_row).sum());
+        for token in doc {
+            let count = doc.iter().filter(|t| *t == token).count() as u64;
+            assert_eq!(count, x[[index_row, vectorizer.vocabulary_[token] as usize]]);
+        }
+    }
+}
+
+#[test]
+fn test_count_dtypes() {
+    let docs = vec!["apple banana apple", "banana orange"];
+
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(docs.clone());
+    let x_u8 = vectorizer.fit_transform_as::<u8>(docs.clone()).unwrap();
+    let x_u32 = vectorizer.transform_as::<u32>(docs.clone()).unwrap();
+    let x_f32 = vectorizer.transform_as::<f32>(docs).unwrap();
+
+    assert_eq!(x.mapv(|e| e as u8), x_u8);
+    assert_eq!(x.mapv(|e| e as u32), x_u32);
+    assert_eq!(x.mapv(|e| e as f32), x_f32);
+}
+
+#[test]
+fn test_count_dtype_overflow() {
+    let doc = vec!["apple"; 70000].join(" ");
+
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    assert!(vectorizer.fit_transform_as::<u16>(vec![doc.as_str()]).is_none());
+    assert_eq!(1, vectorizer.vocabulary_.len());    // fitted regardless
+    assert!(vectorizer.transform_as::<u8>(vec![doc.as_str()]).is_none());
+    assert_eq!(70000, vectorizer.transform_as::<u32>(vec![doc.as_str()]).unwrap()[[0, 0]]);
+}
+
+#[test]
+fn test_inverse_transform() {
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(vec!["apple banana apple orange", "", "banana"]);
+
+    assert_eq!(vec!["apple", "banana", "orange"], vectorizer.get_feature_names());
+    let tokens = vectorizer.inverse_transform(&x);
+    assert_eq!(vec!["apple", "banana", "orange"], tokens[0]);
+    assert!(tokens[1].is_empty());
+    assert_eq!(vec!["banana"], tokens[2]);
+
+    let weights = vectorizer.inverse_transform_with_weights(&x);
+    assert_eq!(vec![
+        ("apple".to_string(), 2), ("banana".to_string(), 1), ("orange".to_string(), 1)],
+        weights[0]);
+}
@@ -0,0 +1,69 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::ctfidftransformer::ClassTfidfTransformer;
+
+#[test]
+fn test_fit(){
+    let counts = array![
+        [1, 1, 0],
+        [1, 0, 0],
+        [0, 1, 2]];
+    let labels = vec!["b", "a", "b"];
+
+    let mut transformer = ClassTfidfTransformer::new();
+    let ctfidf = transformer.fit_transform(&counts, &labels);
+
+    assert_eq!(vec!["a", "b"], transformer.classes_);
+    assert_eq!(array![[1, 0, 0], [1, 2, 2]], transformer.class_counts_);
+
+    // 6 words in 2 classes: A = 3
+    let idf = array![(1f64 + 3. / 2.).ln(), (1f64 + 3. / 2.).ln(), (1f64 + 3. / 2.).ln()];
+    assert!((&transformer.idf_ - &idf).mapv(f64::abs).sum() < 1e-12);
+
+    let expected = array![
+        [idf[0], 0., 0.],
+        [idf[0] / 5., idf[1] * 2. / 5., idf[2] * 2. / 5.]];
+    assert!((&ctfidf - &expected).mapv(f64::abs).sum() < 1e-12);
+}
+
+#[test]
+fn test_reduce_frequent_words(){
+    let counts = array![[1, 3], [4, 0]];
+    let labels = vec![0, 1];
+
+    let mut transformer = ClassTfidfTransformer::new();
+    transformer.reduce_frequent_words = true;
+    let ctfidf = transformer.fit_transform(&counts, &labels);
+
+    let expected = array![
+        [0.25f64.sqrt() * transformer.idf_[0], 0.75f64.sqrt() * transformer.idf_[1]],
+        [transformer.idf_[0], 0.]];
+    assert!((&ctfidf - &expected).mapv(f64::abs).sum() < 1e-12);
+}
+
+#[test]
+fn test_transform_and_top_terms(){
+    let counts = array![
+        [2, 0, 1],
+        [0, 3, 1]];
+    let labels = vec![0, 1];
+
+    let mut transformer = ClassTfidfTransformer::new();
+    transformer.fit(&counts, &labels);
+
+    // A single document is weighted like a class
+    let transformed = transformer.transform(&array![[0, 3, 1]]);
+    assert_eq!(transformer.ctfidf_.row(1), transformed.row(0));
+
+    // Unused terms and empty rows stay at 0
+    let empty = transformer.transform(&array![[0, 0, 0]]);
+    assert_eq!(array![[0., 0., 0.]], empty);
+
+    let feature_names = vec!["cat".to_string(), "dog".to_string(), "the".to_string()];
+    let top_terms = transformer.top_terms(&feature_names, 2);
+    assert_eq!("cat", top_terms[0][0].0);
+    assert_eq!("the", top_terms[0][1].0);
+    assert_eq!("dog", top_terms[1][0].0);
+}
@@ -0,0 +1,120 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::countvectorizer::CountVectorizer;
+use vectorizer::featureselection::{self, SelectKBest, SelectPercentile};
+use vectorizer::tfidfvectorizer::TfidfVectorizer;
+
+fn corpus() -> (Vec<&'static str>, Vec<u8>) {
+    let docs = vec![
+        "great movie great acting",
+        "great plot and fine acting",
+        "bad movie bad plot",
+        "boring and bad acting",
+    ];
+    (docs, vec![1, 1, 0, 0])
+}
+
+#[test]
+fn test_scores(){
+    let x = array![
+        [1.0, 0.0, 2.0],
+        [2.0, 0.0, 2.0],
+        [0.0, 0.0, 2.0],
+        [0.0, 0.0, 2.0],
+        [0.0, 0.0, 2.0],
+        [0.0, 0.0, 2.0]];
+    let labels = vec!["a", "a", "b", "b", "b", "b"];
+
+    // Column 0: observed [3, 0], expected [1, 2] -> 4 + 2
+    let chi2 = featureselection::chi2(&x, &labels);
+    assert_eq!(array![6.0, 0.0, 0.0], chi2);
+
+    // Column 0 is present exactly in class "a": MI equals the label entropy
+    let mi = featureselection::mutual_info(&x, &labels);
+    let entropy = -(1. / 3. * (1f64 / 3.).ln() + 2. / 3. * (2f64 / 3.).ln());
+    assert!((mi[0] - entropy).abs() < 1e-12);
+    assert_eq!(0.0, mi[1]);
+    assert!(mi[2].abs() < 1e-12);
+}
+
+#[test]
+fn test_select_and_restrict_count_vectorizer(){
+    let (docs, labels) = corpus();
+    let mut vectorizer = CountVectorizer::new((1, 2), "lower");
+    let x = vectorizer.fit_transform(docs.clone());
+
+    for &score_func in &["chi2", "mutual_info"] {
+        let mut selector = SelectKBest::new(score_func, 3);
+        let x_selected = selector.fit_transform(&x, &labels);
+        assert_eq!((4, 3), x_selected.dim());
+        assert!(selector.support_.windows(2).all(|w| w[0] < w[1]));
+
+        let mut restricted = CountVectorizer::new((1, 2), "lower");
+        restricted.fit(docs.clone());
+        restricted.restrict_vocabulary(&selector.support_);
+        assert_eq!(3, restricted.vocabulary_.len());
+        assert_eq!(x_selected, restricted.transform(docs.clone()));
+        assert!(restricted.get_feature_names().contains(&"great".to_string()));
+        assert!(restricted.get_feature_names().contains(&"bad".to_string()));
+    }
+}
+
+#[test]
+fn test_select_percentile_and_restrict_tfidf_vectorizer(){
+    let (docs, labels) = corpus();
+    let mut counter = CountVectorizer::new((1, 1), "lower");
+    let counts = counter.fit_transform(docs.clone());
+    let num_columns = counts.dim().1;
+
+    let mut selector = SelectPercentile::new("chi2", 25.);
+    selector.fit(&counts, &labels);
+    assert_eq!(num_columns.div_ceil(4), selector.support_.len());
+
+    // The Tf-Idf vectorizer has the same vocabulary, so the support applies
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    vectorizer.norm = "none".to_string();
+    let x = vectorizer.fit_transform(docs.clone());
+    vectorizer.restrict_vocabulary(&selector.support_);
+
+    assert_eq!(selector.support_.len(), vectorizer.idf_.len());
+    assert_eq!(selector.support_.len(), vectorizer.df_.len());
+    assert_eq!(selector.transform(&x), vectorizer.transform(docs));
+}
+
+#[test]
+fn test_select_percentile_count(){
+    // 10 percent of 30 columns is exactly 3, 12.5 percent rounds up to 4
+    let x = ndarray::Array2::from_shape_fn((2, 30), |(i, j)| ((i + j) % 3) as u64);
+    let labels = vec![0, 1];
+
+    let mut selector = SelectPercentile::new("chi2", 10.);
+    selector.fit(&x, &labels);
+    assert_eq!(3, selector.support_.len());
+
+    selector.percentile = 12.5;
+    selector.fit(&x, &labels);
+    assert_eq!(4, selector.support_.len());
+
+    // 28. / 100. * 25. is slightly above 7 in floating point
+    selector.percentile = 28.;
+    selector.fit(&x.slice(s![.., ..25]).to_owned(), &labels);
+    assert_eq!(7, selector.support_.len());
+}
+
+#[test]
+#[should_panic(expected = "out of the vocabulary")]
+fn test_restrict_count_vectorizer_out_of_vocabulary(){
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    vectorizer.fit(vec!["apple banana"]);
+    vectorizer.restrict_vocabulary(&[0, 2]);
+}
+
+#[test]
+#[should_panic(expected = "out of the vocabulary")]
+fn test_restrict_tfidf_vectorizer_out_of_vocabulary(){
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    vectorizer.fit(vec!["apple banana"]);
+    vectorizer.restrict_vocabulary(&[5]);
+}
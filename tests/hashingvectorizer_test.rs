@@ -0,0 +1,82 @@
+extern crate vectorizer;
+
+use vectorizer::hashingvectorizer::{murmurhash3_32, HashingVectorizer};
+
+
+#[test]
+fn test_murmurhash3_32(){
+    // Reference values of MurmurHash3_x86_32
+    assert_eq!(0, murmurhash3_32(b"", 0));
+    assert_eq!(0x514e_28b7, murmurhash3_32(b"", 1));
+    assert_eq!(0x81f1_6f39, murmurhash3_32(b"", 0xffff_ffff));
+    assert_eq!(0x7629_3b50, murmurhash3_32(&[0xff, 0xff, 0xff, 0xff], 0));
+    assert_eq!(0xf55b_516b, murmurhash3_32(&[0x21, 0x43, 0x65, 0x87], 0));
+    assert_eq!(0x7e4a_8634, murmurhash3_32(&[0x21, 0x43, 0x65], 0));
+    assert_eq!(0xa0f7_b07a, murmurhash3_32(&[0x21, 0x43], 0));
+    assert_eq!(0x7266_1cf4, murmurhash3_32(&[0x21], 0));
+}
+
+#[test]
+fn test_identical_across_instances(){
+    let docs = vec![
+        "apple banana apple",
+        "orange banana",
+        ""];
+
+    let mut vectorizer1 = HashingVectorizer::new((1, 2), "lower");
+    let mut vectorizer2 = HashingVectorizer::new((1, 2), "lower");
+    vectorizer1.n_features = 64;
+    vectorizer2.n_features = 64;
+
+    let x1 = vectorizer1.transform(docs.clone());
+    let x2 = vectorizer2.fit_transform(docs);
+    assert_eq!((3, 64), x1.dim());
+    assert_eq!(x1.indptr, x2.indptr);
+    assert_eq!(x1.indices, x2.indices);
+    assert_eq!(x1.data, x2.data);
+
+    // Empty document gives an empty row
+    assert_eq!(0, x1.row(2).0.len());
+}
+
+#[test]
+fn test_counts_without_sign_and_norm(){
+    let docs = vec!["apple banana apple"];
+
+    let mut vectorizer = HashingVectorizer::new((1, 1), "lower");
+    vectorizer.n_features = 1 << 10;
+    vectorizer.alternate_sign = false;
+    vectorizer.norm = "none".to_string();
+    let x = vectorizer.transform(docs);
+
+    let apple = (murmurhash3_32(b"apple", 0) as i32).unsigned_abs() as usize % (1 << 10);
+    let banana = (murmurhash3_32(b"banana", 0) as i32).unsigned_abs() as usize % (1 << 10);
+    let dense = x.to_dense();
+    assert_eq!(2, x.nnz());
+    assert_eq!(2., dense[[0, apple]]);
+    assert_eq!(1., dense[[0, banana]]);
+}
+
+#[test]
+fn test_l2_norm_and_seed(){
+    let docs = vec!["one two three four five six"];
+
+    let mut vectorizer = HashingVectorizer::new((1, 1), "lower");
+    let x = vectorizer.transform(docs.clone());
+    let norm: f64 = x.data.iter().map(|e| e * e).sum::<f64>().sqrt();
+    assert!((norm - 1.).abs() < 1e-12);
+
+    // A different seed maps tokens to different columns
+    let indices_seed0 = x.indices.clone();
+    vectorizer.seed = 42;
+    let x_seed42 = vectorizer.transform(docs);
+    assert_ne!(indices_seed0, x_seed42.indices);
+}
+
+#[test]
+#[should_panic(expected = "n_features must be positive")]
+fn test_zero_features(){
+    let mut vectorizer = HashingVectorizer::new((1, 1), "lower");
+    vectorizer.n_features = 0;
+    vectorizer.transform(vec!["some text"]);
+}
@@ -0,0 +1,104 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::kmeans::KMeans;
+use vectorizer::tfidfvectorizer::TfidfVectorizer;
+
+fn blobs() -> ndarray::Array2<f64> {
+    array![
+        [0.0, 0.1], [0.2, 0.0], [0.1, 0.2],
+        [5.0, 5.1], [5.2, 4.9], [4.9, 5.0],
+        [0.0, 9.0], [0.2, 9.1], [0.1, 8.8]]
+}
+
+fn assert_same_partition(labels: &[usize]) {
+    for group in labels.chunks(3) {
+        assert!(group.iter().all(|&label| label == group[0]));
+    }
+    assert_ne!(labels[0], labels[3]);
+    assert_ne!(labels[0], labels[6]);
+    assert_ne!(labels[3], labels[6]);
+}
+
+#[test]
+fn test_euclidean_full_and_mini_batch(){
+    let x = blobs();
+    for &batch_size in &[None, Some(3)] {
+        let mut kmeans = KMeans::new(3);
+        kmeans.metric = "euclidean".to_string();
+        kmeans.batch_size = batch_size;
+        kmeans.n_init = 3;
+        let labels = kmeans.fit_predict(&x);
+
+        assert_same_partition(&labels);
+        assert!(kmeans.inertia_ < 1.0, "{:?}: {}", batch_size, kmeans.inertia_);
+        assert_eq!(labels, kmeans.predict(&x));
+        let distances = kmeans.transform(&x);
+        assert_eq!((9, 3), distances.dim());
+        assert!((distances[[0, labels[0]]] - distances.row(0).fold(f64::INFINITY, |a, &b| a.min(b))).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_spherical_ignores_length(){
+    // Same directions at very different lengths
+    let x = array![
+        [1.0, 0.0, 0.1],
+        [10.0, 0.5, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.1, 20.0, 0.0]];
+    let mut kmeans = KMeans::new(2);
+    let labels = kmeans.fit_predict(&x);
+    assert_eq!(labels[0], labels[1]);
+    assert_eq!(labels[2], labels[3]);
+    assert_ne!(labels[0], labels[2]);
+
+    // Centroids have unit length
+    for center in kmeans.cluster_centers_.outer_iter() {
+        assert!((center.dot(&center) - 1.).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_top_terms_of_tickets(){
+    let docs = vec![
+        "cannot login to account",
+        "login password reset",
+        "password login error",
+        "refund for order",
+        "order refund missing",
+        "refund order delayed",
+    ];
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(docs);
+
+    let mut kmeans = KMeans::new(2);
+    kmeans.batch_size = Some(2);
+    kmeans.seed = 3;
+    kmeans.fit(&x);
+
+    let mut top: Vec<Vec<String>> = kmeans.top_terms(&vectorizer.get_feature_names(), 2).into_iter()
+        .map(|terms| {
+            let mut terms: Vec<String> = terms.into_iter().map(|(term, _)| term).collect();
+            terms.sort();
+            terms
+        })
+        .collect();
+    top.sort();
+    assert_eq!(vec![vec!["login", "password"], vec!["order", "refund"]], top);
+}
+
+#[test]
+#[should_panic(expected = "n_clusters must be positive")]
+fn test_zero_clusters(){
+    let mut kmeans = KMeans::new(0);
+    kmeans.fit(&blobs());
+}
+
+#[test]
+#[should_panic(expected = "fewer documents than clusters")]
+fn test_more_clusters_than_documents(){
+    let mut kmeans = KMeans::new(10);
+    kmeans.fit(&blobs());
+}
@@ -0,0 +1,86 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::countvectorizer::CountVectorizer;
+use vectorizer::lda::LatentDirichletAllocation;
+
+fn corpus() -> Vec<&'static str> {
+    vec![
+        "cat dog cat pet",
+        "dog cat pet dog",
+        "pet cat dog",
+        "stocks markets trade",
+        "markets stocks stocks",
+        "trade markets stocks",
+    ]
+}
+
+#[test]
+fn test_topics_separate_documents(){
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    let counts = vectorizer.fit_transform(corpus());
+
+    for &method in &["batch", "online"] {
+        let mut lda = LatentDirichletAllocation::new(2);
+        lda.learning_method = method.to_string();
+        lda.batch_size = 2;
+        lda.max_iter = 50;
+        let doc_topic = lda.fit_transform(&counts);
+
+        // Rows are distributions
+        assert!(doc_topic.outer_iter().all(|row| (row.sum() - 1.).abs() < 1e-10));
+
+        // Documents of the same subject share their dominant topic
+        let dominant: Vec<usize> = doc_topic.outer_iter()
+            .map(|row| if row[0] > row[1] { 0 } else { 1 })
+            .collect();
+        assert_eq!(dominant[0], dominant[1]);
+        assert_eq!(dominant[0], dominant[2]);
+        assert_eq!(dominant[3], dominant[4]);
+        assert_eq!(dominant[3], dominant[5]);
+        assert_ne!(dominant[0], dominant[3]);
+
+        let top_terms = lda.top_terms(&vectorizer.get_feature_names(), 1);
+        assert_eq!(2, top_terms.len());
+    }
+}
+
+#[test]
+fn test_fit_is_deterministic_and_lowers_perplexity(){
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    let counts = vectorizer.fit_transform(corpus());
+
+    let mut lda1 = LatentDirichletAllocation::new(2);
+    let mut lda2 = LatentDirichletAllocation::new(2);
+    lda1.fit(&counts);
+    lda2.fit(&counts);
+    assert_eq!(lda1.components_, lda2.components_);
+
+    let mut short = LatentDirichletAllocation::new(2);
+    short.max_iter = 1;
+    short.fit(&counts);
+    assert!(lda1.perplexity(&counts) < short.perplexity(&counts));
+    assert!(lda1.perplexity(&counts) > 1.);
+}
+
+#[test]
+fn test_partial_fit_with_growing_vocabulary(){
+    let docs = corpus();
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    let mut lda = LatentDirichletAllocation::new(2);
+    lda.total_samples = 6.;
+
+    vectorizer.partial_fit(docs[..3].to_vec());
+    lda.partial_fit(&vectorizer.transform(docs[..3].to_vec()));
+    assert_eq!((2, 3), lda.components_.dim());
+
+    vectorizer.partial_fit(docs[3..].to_vec());
+    let counts = vectorizer.transform(docs.clone());
+    lda.partial_fit(&counts.slice(s![3.., ..]).to_owned());
+    assert_eq!((2, 6), lda.components_.dim());
+
+    let doc_topic = lda.transform(&counts);
+    assert_eq!((6, 2), doc_topic.dim());
+    assert!(doc_topic.iter().all(|e| e.is_finite()));
+}
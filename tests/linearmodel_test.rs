@@ -0,0 +1,122 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::linearmodel::{balanced_class_weight, SgdClassifier};
+use vectorizer::sparse::CsrMatrix;
+use vectorizer::tfidfvectorizer::TfidfVectorizer;
+
+fn corpus() -> (Vec<&'static str>, Vec<&'static str>) {
+    let docs = vec![
+        "book a flight to paris",
+        "cheap flight tickets",
+        "hotel booking in rome",
+        "what is the weather today",
+        "will it rain tomorrow",
+        "sunny weather this weekend",
+        "play some jazz music",
+        "next song please",
+        "turn up the music",
+    ];
+    let labels = vec![
+        "travel", "travel", "travel",
+        "weather", "weather", "weather",
+        "music", "music", "music"];
+    (docs, labels)
+}
+
+#[test]
+fn test_multiclass_one_vs_rest(){
+    let (docs, labels) = corpus();
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = CsrMatrix::from_dense(&vectorizer.fit_transform(docs));
+    let x_new = CsrMatrix::from_dense(&vectorizer.transform(vec![
+        "flight to rome", "rain this weekend", "jazz song"]));
+
+    for &loss in &["hinge", "log"] {
+        for &penalty in &["l2", "l1", "none"] {
+            let mut classifier = SgdClassifier::new();
+            classifier.loss = loss.to_string();
+            classifier.penalty = penalty.to_string();
+            classifier.fit(&x, &labels);
+
+            assert_eq!(vec!["music", "travel", "weather"], classifier.classes_);
+            assert_eq!(3, classifier.coef_.dim().0);
+            assert_eq!(labels, classifier.predict(&x), "{} {}", loss, penalty);
+            assert_eq!(vec!["travel", "weather", "music"], classifier.predict(&x_new), "{} {}", loss, penalty);
+        }
+    }
+}
+
+#[test]
+fn test_binary_probabilities_and_coefficients(){
+    let (docs, labels) = corpus();
+    let labels: Vec<bool> = labels.iter().map(|&label| label == "weather").collect();
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = CsrMatrix::from_dense(&vectorizer.fit_transform(docs));
+
+    let mut classifier = SgdClassifier::new();
+    classifier.loss = "log".to_string();
+    classifier.fit(&x, &labels);
+    assert_eq!(vec![false, true], classifier.classes_);
+    assert_eq!(1, classifier.coef_.dim().0);
+
+    let proba = classifier.predict_proba(&x);
+    assert!(proba.outer_iter().all(|row| (row.sum() - 1.).abs() < 1e-12));
+    for (row, &label) in proba.outer_iter().zip(labels.iter()) {
+        assert_eq!(label, row[1] > 0.5);
+    }
+
+    let feature_names = vectorizer.get_feature_names();
+    let top = classifier.top_features(&feature_names, 1);
+    assert_eq!("weather", top[0][0].0);
+    assert!(classifier.coefficient(&feature_names, "weather").unwrap()[0] > 0.);
+    assert!(classifier.coefficient(&feature_names, "flight").unwrap()[0] < 0.);
+    assert!(classifier.coefficient(&feature_names, "unknown").is_none());
+}
+
+#[test]
+fn test_l1_gives_sparse_coefficients(){
+    let (docs, labels) = corpus();
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = CsrMatrix::from_dense(&vectorizer.fit_transform(docs));
+
+    let mut l1 = SgdClassifier::new();
+    l1.penalty = "l1".to_string();
+    l1.alpha = 0.01;
+    l1.fit(&x, &labels);
+    let mut l2 = SgdClassifier::new();
+    l2.alpha = 0.01;
+    l2.fit(&x, &labels);
+
+    let zeros = |coef: &ndarray::Array2<f64>| coef.iter().filter(|&&e| e == 0.).count();
+    assert!(zeros(&l1.coef_) > zeros(&l2.coef_));
+}
+
+#[test]
+fn test_class_weight(){
+    let labels = vec![0, 0, 0, 1];
+    let weights = balanced_class_weight(&labels);
+    assert_eq!(4. / 6., weights[&0]);
+    assert_eq!(2., weights[&1]);
+
+    // Identical documents with imbalanced labels: the prediction follows the
+    // weighted majority
+    let x = CsrMatrix::from_dense(&array![
+        [1.0, 1.0],
+        [1.0, 1.0],
+        [1.0, 1.0],
+        [1.0, 1.0]]);
+    let mut unweighted = SgdClassifier::new();
+    unweighted.loss = "log".to_string();
+    unweighted.eta0 = 0.05;
+    unweighted.fit(&x, &labels);
+    assert_eq!(vec![0], unweighted.predict(&CsrMatrix::from_dense(&array![[1.0, 1.0]])));
+
+    let mut weighted = SgdClassifier::new();
+    weighted.loss = "log".to_string();
+    weighted.eta0 = 0.05;
+    weighted.class_weight = Some(vec![(1, 5.0)].into_iter().collect());
+    weighted.fit(&x, &labels);
+    assert_eq!(vec![1], weighted.predict(&CsrMatrix::from_dense(&array![[1.0, 1.0]])));
+}
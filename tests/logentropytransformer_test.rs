@@ -0,0 +1,83 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::countvectorizer::CountVectorizer;
+use vectorizer::logentropytransformer::LogEntropyTransformer;
+use vectorizer::lsa::TruncatedSvd;
+
+#[test]
+fn test_fit_transform(){
+    let counts = array![
+        [2, 1, 0],
+        [2, 0, 0],
+        [0, 3, 1],
+        [0, 0, 0]];
+
+    let mut transformer = LogEntropyTransformer::new();
+    let x = transformer.fit_transform(&counts);
+    assert_eq!(4, transformer.n_samples_);
+
+    // Column 0: p = [0.5, 0.5, 0, 0], column 1: p = [0.25, 0.75, 0, 0]
+    let n = 4f64.ln();
+    let weights = array![
+        1. + 0.5f64.ln() / n,
+        1. + (0.25 * 0.25f64.ln() + 0.75 * 0.75f64.ln()) / n,
+        1.];
+    assert!((&transformer.global_weights_ - &weights).mapv(f64::abs).sum() < 1e-12);
+
+    let expected = counts.mapv(|e| (1. + e as f64).ln()) * &weights;
+    assert!((&x - &expected).mapv(f64::abs).sum() < 1e-12);
+    assert_eq!(array![0., 0., 0.], x.row(3));
+}
+
+#[test]
+fn test_single_document(){
+    let mut transformer = LogEntropyTransformer::new();
+    transformer.fit(&array![[1, 0, 4]]);
+    assert_eq!(array![1., 1., 1.], transformer.global_weights_);
+}
+
+#[test]
+fn test_norm(){
+    let counts = array![[1, 3], [2, 0]];
+    let mut transformer = LogEntropyTransformer::new();
+    transformer.norm = "l2".to_string();
+    let x = transformer.fit_transform(&counts);
+
+    for row in x.outer_iter() {
+        assert!((row.dot(&row) - 1.).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_pivoted_norm(){
+    let counts = array![[1, 3], [2, 0]];
+    let mut transformer = LogEntropyTransformer::new();
+    transformer.norm = "pivoted".to_string();
+    let x = transformer.fit_transform(&counts);
+
+    // 3 unique terms over 2 documents
+    assert_eq!(1.5, transformer.pivot_);
+    let weight = transformer.global_weights_[0];
+    assert!((x[[1, 0]] - 3f64.ln() * weight / (0.8 * 1.5 + 0.2)).abs() < 1e-12);
+}
+
+#[test]
+fn test_lsa_pipeline(){
+    let docs = vec![
+        "human machine interface",
+        "user interface system",
+        "graph of trees",
+        "graph minors and trees",
+    ];
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    let counts = vectorizer.fit_transform(docs);
+
+    let mut transformer = LogEntropyTransformer::new();
+    let x = transformer.fit_transform(&counts);
+
+    let mut svd = TruncatedSvd::new(2);
+    let topics = svd.fit_transform(&x);
+    assert_eq!((4, 2), topics.dim());
+}
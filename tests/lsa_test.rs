@@ -0,0 +1,80 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use ndarray::Array2;
+use vectorizer::linalg;
+use vectorizer::lsa::TruncatedSvd;
+use vectorizer::sparse::CsrMatrix;
+use vectorizer::tfidfvectorizer::TfidfVectorizer;
+
+fn assert_close(a: &Array2<f64>, b: &Array2<f64>, tolerance: f64) {
+    assert_eq!(a.dim(), b.dim());
+    assert!(a.iter().zip(b.iter()).all(|(x, y)| (x - y).abs() < tolerance),
+        "\n{:?}\n!=\n{:?}", a, b);
+}
+
+#[test]
+fn test_randomized_svd_reconstructs_low_rank(){
+    // Rank 2 matrix
+    let left = array![[1.0, 0.0], [2.0, 1.0], [0.0, 3.0], [1.0, 1.0], [4.0, 0.5]];
+    let right = array![[1.0, 0.0, 2.0, 0.0, 1.0, 3.0], [0.0, 1.0, 1.0, 2.0, 0.0, 0.5]];
+    let x = left.dot(&right);
+
+    let (u, s, vt) = linalg::randomized_svd(&x, 2, 4, 2, 42);
+    assert!(s[0] >= s[1] && s[1] > 0.);
+    assert_close(&u.t().dot(&u), &Array2::eye(2), 1e-10);
+    assert_close(&vt.dot(&vt.t()), &Array2::eye(2), 1e-10);
+
+    let reconstructed = (u * &s.into_shape((1, 2)).unwrap()).dot(&vt);
+    assert_close(&x, &reconstructed, 1e-9);
+}
+
+#[test]
+fn test_sparse_matches_dense(){
+    let docs = vec![
+        "the cat sat on the mat",
+        "the cat chased the dog",
+        "the dog sat on the log",
+        "stocks fell as markets closed",
+        "markets rallied and stocks rose",
+        "investors sold stocks",
+    ];
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(docs);
+    let x_sparse = CsrMatrix::from_dense(&x);
+
+    let mut lsa = TruncatedSvd::new(2);
+    let mut lsa_sparse = TruncatedSvd::new(2);
+    let reduced = lsa.fit_transform(&x);
+    let reduced_sparse = lsa_sparse.fit_transform(&x_sparse);
+
+    assert_close(&reduced, &reduced_sparse, 1e-10);
+    assert_close(&lsa.components_, &lsa_sparse.components_, 1e-10);
+    assert_close(&reduced, &lsa.transform(&x), 1e-10);
+
+    // Ratios are positive and cannot explain more than the total variance
+    let ratio_sum: f64 = lsa.explained_variance_ratio_.sum();
+    assert!(lsa.explained_variance_ratio_.iter().all(|&e| e > 0.));
+    assert!(ratio_sum <= 1.0 + 1e-12);
+
+    // The two topics are separated by the leading components
+    let top_terms = lsa.top_terms(&vectorizer.get_feature_names(), 3);
+    assert_eq!(2, top_terms.len());
+    assert_eq!(3, top_terms[0].len());
+    assert!(top_terms[0][0].1 >= top_terms[0][1].1);
+}
+
+#[test]
+fn test_transform_new_documents(){
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(vec!["apple banana", "banana orange", "kiwi apple"]);
+    let mut lsa = TruncatedSvd::new(2);
+    lsa.seed = 7;
+    lsa.fit(&x);
+
+    let new_docs = vectorizer.transform(vec!["apple kiwi kiwi", "unknown words"]);
+    let reduced = lsa.transform(&new_docs);
+    assert_eq!((2, 2), reduced.dim());
+    assert_eq!(array![0.0, 0.0], reduced.row(1));
+}
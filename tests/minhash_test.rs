@@ -0,0 +1,127 @@
+extern crate vectorizer;
+
+use vectorizer::minhash::{self, MinHash, MinHashLsh};
+use vectorizer::tokenizer::Tokenizer;
+
+#[test]
+fn test_char_shingles(){
+    let mut tokenizer = Tokenizer::new((2, 3), "lower");
+    tokenizer.analyzer = "char".to_string();
+    let tokens = tokenizer.tokenize(vec!["Ab  c", ""]);
+
+    assert_eq!(vec!["ab", "b ", " c", "ab ", "b c"], tokens[0]);
+    assert!(tokens[1].is_empty());
+}
+
+#[test]
+fn test_signatures(){
+    let minhash = MinHash::new((1, 1), "lower");
+    let docs = vec![
+        "alpha beta gamma",
+        "gamma beta alpha alpha",
+        "ALPHA beta gamma",
+        "",
+    ];
+    let signatures = minhash.transform(docs.clone());
+
+    // Signatures only depend on the set of shingles
+    assert_eq!(signatures.row(0), signatures.row(1));
+    assert_eq!(signatures.row(0), signatures.row(2));
+    assert_eq!(1.0, minhash::jaccard(signatures.row(0), signatures.row(1)));
+    assert!(signatures.row(3).iter().all(|&e| e == u32::MAX));
+
+    // Same options give the same signatures, another seed other ones
+    assert_eq!(signatures, MinHash::new((1, 1), "lower").transform(docs.clone()));
+    let mut other = MinHash::new((1, 1), "lower");
+    other.seed = 2;
+    other.num_perm = 64;
+    let other_signatures = other.transform(docs);
+    assert_eq!((4, 64), other_signatures.dim());
+    assert_ne!(signatures.row(0).to_vec()[..64], other_signatures.row(0).to_vec()[..]);
+}
+
+#[test]
+fn test_jaccard_estimate(){
+    // Shingle sets {0..80} and {20..100}: Jaccard similarity 60 / 100
+    let doc_a: Vec<String> = (0..80).map(|i| format!("w{}", i)).collect();
+    let doc_b: Vec<String> = (20..100).map(|i| format!("w{}", i)).collect();
+    let doc_a = doc_a.join(" ");
+    let doc_b = doc_b.join(" ");
+
+    let mut minhash = MinHash::new((1, 1), "lower");
+    minhash.num_perm = 512;
+    let signatures = minhash.transform(vec![doc_a.as_str(), doc_b.as_str()]);
+
+    let estimate = minhash::jaccard(signatures.row(0), signatures.row(1));
+    assert!((estimate - 0.6).abs() < 0.1, "estimate: {}", estimate);
+}
+
+#[test]
+fn test_bands(){
+    // Higher thresholds need longer bands
+    let low = MinHashLsh::new(0.3, 128);
+    let high = MinHashLsh::new(0.9, 128);
+    assert!(low.bands * low.rows <= 128);
+    assert!(high.bands * high.rows <= 128);
+    assert!(low.rows < high.rows);
+    assert!(low.bands > high.bands);
+}
+
+#[test]
+#[should_panic(expected = "num_perm")]
+fn test_zero_permutations(){
+    MinHashLsh::new(0.5, 0);
+}
+
+#[test]
+fn test_lsh(){
+    let docs = vec![
+        "the council approved the new budget for the city parks on monday",
+        "a recipe for lemon cake with a crunchy sugar topping",
+        "the council approved the new budget for the city parks on tuesday",
+        "the council approved the new budget for the city parks on monday.",
+        "weather forecast: rain expected across the region this weekend",
+    ];
+    let mut minhash = MinHash::new((4, 4), "lower");
+    minhash.analyzer = "char".to_string();
+    let signatures = minhash.transform(docs);
+
+    let mut lsh = MinHashLsh::new(0.7, minhash.num_perm);
+    lsh.fit(&signatures);
+    let rows: Vec<Vec<u32>> = signatures.outer_iter().map(|row| row.to_vec()).collect();
+    assert_eq!(rows, lsh.signatures_);
+
+    let pairs: Vec<(usize, usize)> = lsh.duplicate_pairs().iter()
+        .map(|&(i, j, _)| (i, j))
+        .collect();
+    assert_eq!(vec![(0, 2), (0, 3), (2, 3)], pairs);
+    for (i, j, similarity) in lsh.duplicate_pairs() {
+        assert_eq!(minhash::jaccard(signatures.row(i), signatures.row(j)), similarity);
+    }
+    for pair in pairs.iter() {
+        assert!(lsh.candidate_pairs().contains(pair));
+    }
+    assert_eq!(vec![1], lsh.query(signatures.row(1)));
+}
+
+#[test]
+fn test_partial_fit(){
+    let mut minhash = MinHash::new((3, 3), "lower");
+    minhash.analyzer = "char".to_string();
+    let day_1 = minhash.transform(vec!["first scraped page", "second scraped page"]);
+    let day_2 = minhash.transform(vec!["a new page", "first scraped page"]);
+
+    let mut lsh = MinHashLsh::new(0.9, minhash.num_perm);
+    lsh.fit(&day_1);
+    assert_eq!(vec![0], lsh.query(day_2.row(1)));
+
+    // New documents get the following IDs
+    lsh.partial_fit(&day_2);
+    assert_eq!(4, lsh.signatures_.len());
+    assert_eq!(vec![0, 3], lsh.query(day_2.row(1)));
+    assert!(lsh.duplicate_pairs().iter().any(|&(i, j, _)| (i, j) == (0, 3)));
+
+    // fit() discards the previous documents
+    lsh.fit(&day_2);
+    assert_eq!(vec![1], lsh.query(day_2.row(1)));
+}
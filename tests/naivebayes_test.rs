@@ -0,0 +1,133 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use ndarray::Array2;
+use vectorizer::countvectorizer::CountVectorizer;
+use vectorizer::naivebayes::{BernoulliNb, MultinomialNb};
+
+#[test]
+fn test_multinomial_probabilities(){
+    // Hand computed: class 0 has feature counts [2, 1, 0], class 1 [0, 1, 3]
+    let x = array![
+        [2, 0, 0],
+        [0, 1, 0],
+        [0, 1, 3]];
+    let labels = vec![0, 0, 1];
+    let mut classifier = MultinomialNb::new();
+    classifier.fit(&x, &labels);
+
+    assert_eq!(vec![0, 1], classifier.classes_);
+    assert_eq!(array![2., 1.], classifier.class_count_);
+    let expected = array![
+        [3. / 6., 2. / 6., 1. / 6.],
+        [1. / 7., 2. / 7., 4. / 7.]].mapv(f64::ln);
+    assert!(classifier.feature_log_prob_.iter().zip(expected.iter()).all(|(a, b)| (a - b).abs() < 1e-12));
+
+    let proba = classifier.predict_proba(&array![[1, 0, 0], [0, 0, 1]]);
+    assert!(proba.outer_iter().all(|row| (row.sum() - 1.).abs() < 1e-12));
+    assert_eq!(vec![0, 1], classifier.predict(&array![[1, 0, 0], [0, 0, 1]]));
+
+    // Uniform priors with Lidstone smoothing
+    classifier.fit_prior = false;
+    classifier.alpha = 0.5;
+    classifier.fit(&x, &labels);
+    assert_eq!(classifier.class_log_prior_[0], classifier.class_log_prior_[1]);
+}
+
+#[test]
+fn test_text_classification(){
+    let docs = vec![
+        "great movie loved it",
+        "great story great acting",
+        "loved the story",
+        "boring and bad movie",
+        "bad acting boring story",
+        "bad bad bad",
+    ];
+    let labels: Vec<String> = vec!["pos", "pos", "pos", "neg", "neg", "neg"]
+        .into_iter().map(String::from).collect();
+    let mut vectorizer = CountVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(docs);
+    let x_new = vectorizer.transform(vec!["loved it", "boring"]);
+
+    let mut multinomial = MultinomialNb::new();
+    multinomial.fit(&x, &labels);
+    assert_eq!(vec!["pos", "neg"], multinomial.predict(&x_new));
+
+    let mut bernoulli = BernoulliNb::new();
+    bernoulli.fit(&x, &labels);
+    assert_eq!(vec!["pos", "neg"], bernoulli.predict(&x_new));
+    let log_proba = bernoulli.predict_log_proba(&x_new);
+    assert!(log_proba.iter().all(|&e| e <= 0.));
+
+    // classes_ are sorted, so "neg" comes first
+    let features = multinomial.most_informative_features(&vectorizer.get_feature_names(), 1);
+    assert_eq!("bad", features[0][0].0);
+    assert_eq!("great", features[1][0].0);
+    assert!(features[1][0].1 > 0.);
+}
+
+#[test]
+fn test_bernoulli_uses_absence(){
+    // Feature 1 is always present in class 0, and never in class 1
+    let x = array![
+        [1.0, 0.5],
+        [0.0, 2.0],
+        [1.0, 0.0],
+        [0.0, 0.0]];
+    let mut classifier = BernoulliNb::new();
+    classifier.fit(&x, &["a", "a", "b", "b"]);
+
+    assert_eq!(array![[1., 2.], [1., 0.]], classifier.feature_count_);
+    assert_eq!(vec!["a", "b"], classifier.predict(&array![[0.0, 1.0], [0.0, 0.0]]));
+}
+
+#[test]
+fn test_zero_alpha_is_clamped(){
+    let x = array![
+        [2, 1, 0],
+        [0, 1, 3]];
+    let labels = ["a", "b"];
+
+    let mut multinomial = MultinomialNb::new();
+    multinomial.alpha = 0.;
+    multinomial.fit(&x, &labels);
+    assert!(multinomial.predict_proba(&x).iter().all(|e| e.is_finite()));
+    assert_eq!(vec!["a", "b"], multinomial.predict(&x));
+
+    let mut bernoulli = BernoulliNb::new();
+    bernoulli.alpha = 0.;
+    bernoulli.fit(&x, &labels);
+    assert!(bernoulli.predict_proba(&x).iter().all(|e| e.is_finite()));
+    assert_eq!(vec!["a", "b"], bernoulli.predict(&x));
+}
+
+#[test]
+#[should_panic(expected = "not fitted")]
+fn test_predict_before_fit(){
+    let classifier: MultinomialNb<&str> = MultinomialNb::new();
+    classifier.predict(&array![[1, 0]]);
+}
+
+#[test]
+#[should_panic(expected = "must not be empty")]
+fn test_fit_empty(){
+    let mut classifier: MultinomialNb<&str> = MultinomialNb::new();
+    classifier.fit(&Array2::<u64>::zeros((0, 3)), &[]);
+}
+
+#[test]
+#[should_panic(expected = "x must be non-negative")]
+fn test_multinomial_fit_negative(){
+    let mut classifier = MultinomialNb::new();
+    classifier.fit(&array![[1., -0.5], [0., 2.]], &["a", "b"]);
+}
+
+#[test]
+#[should_panic(expected = "x must be non-negative")]
+fn test_bernoulli_fit_negative(){
+    let mut classifier = BernoulliNb::new();
+    classifier.binarize = None;
+    classifier.fit(&array![[1., -1.], [0., 1.]], &["a", "b"]);
+}
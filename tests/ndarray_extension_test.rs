@@ -1,8 +1,13 @@
+// The original tests are kept as written
+#![allow(clippy::vec_init_then_push, clippy::unnecessary_cast)]
+
 extern crate vectorizer;
 #[macro_use]
 extern crate ndarray;
 
+use ndarray::Axis;
 use vectorizer::ndarray_extension;
+use vectorizer::sparse::CsrMatrix;
 
 #[test]
 #[ignore]
@@ -95,4 +100,170 @@ fn test_l2_normalization(){
     println!("L2 Norms = {:?}", rnorms);
     println!("L2 Matrix = {:?}", l2);
 
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_convert_matrix(){
+    let x = array![
+        [1u64, 0, 65535],
+        [2, 3, 4]];
+
+    let y: ndarray::Array2<u16> = ndarray_extension::convert_matrix(x.clone()).unwrap();
+    assert_eq!(x.mapv(|e| e as u16), y);
+    assert!(ndarray_extension::convert_matrix::<u64, u8>(x.clone()).is_none());
+    assert!(ndarray_extension::convert_matrix::<i64, u64>(array![[-1i64]]).is_none());
+
+    let z: ndarray::Array2<f32> = ndarray_extension::convert_matrix(x.clone()).unwrap();
+    assert_eq!(x.mapv(|e| e as f32), z);
+}
+
+#[test]
+fn test_pairwise_similarity(){
+    let x = array![
+        [1.0, 2.0, 0.0],
+        [0.0, 0.0, 0.0],
+        [3.0, 0.0, 4.0]];
+    let y = array![
+        [2.0, 4.0, 0.0],
+        [0.0, 1.0, 1.0]];
+
+    let dot = ndarray_extension::pairwise_similarity(&x, &y, "dot");
+    assert_eq!(array![[10.0, 2.0], [0.0, 0.0], [6.0, 4.0]], dot);
+
+    let cosine = ndarray_extension::pairwise_similarity(&x, &y, "cosine");
+    assert!((cosine[[0, 0]] - 1.0).abs() < 1e-12);
+    assert_eq!(0.0, cosine[[1, 0]]);    // zero row
+    assert!((cosine[[2, 1]] - 4.0 / (5.0 * f64::sqrt(2.))).abs() < 1e-12);
+
+    let euclidean = ndarray_extension::pairwise_similarity(&x, &y, "euclidean");
+    assert!((euclidean[[0, 0]] - f64::sqrt(5.)).abs() < 1e-12);
+    assert!((euclidean[[1, 1]] - f64::sqrt(2.)).abs() < 1e-12);
+}
+
+#[test]
+fn test_nearest_neighbors_blocks(){
+    let x = array![
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.1],
+        [0.5, 0.5, 0.0],
+        [0.0, 0.0, 2.0],
+        [1.0, 1.0, 1.0]];
+
+    // Results do not depend on the block size
+    let all = ndarray_extension::nearest_neighbors(&x, &x, 3, "cosine", 100);
+    for block_size in 1..5 {
+        assert_eq!(all, ndarray_extension::nearest_neighbors(&x, &x, 3, "cosine", block_size));
+    }
+
+    // Each row is its own nearest neighbor
+    for (index_row, neighbors) in all.iter().enumerate() {
+        assert_eq!(index_row, neighbors[0].0);
+        assert!(neighbors[0].1 >= neighbors[1].1 && neighbors[1].1 >= neighbors[2].1);
+    }
+
+    // Euclidean neighbors are sorted by increasing distance
+    let euclidean = ndarray_extension::nearest_neighbors(&x, &x, 2, "euclidean", 2);
+    assert_eq!(0.0, euclidean[3][0].1);
+    assert!(euclidean[3][1].1 > 0.0);
+}
+
+#[test]
+fn test_sparse_similarity_matches_dense(){
+    let x = array![
+        [1.0, 0.0, 0.0, 2.0],
+        [0.0, 0.0, 0.0, 0.0],
+        [0.0, 3.0, 1.0, 0.0]];
+    let y = array![
+        [0.0, 1.0, 0.0, 1.0],
+        [2.0, 0.0, 0.0, 4.0],
+        [0.0, 0.0, 5.0, 0.0],
+        [1.0, 1.0, 1.0, 1.0]];
+    let x_sparse = CsrMatrix::from_dense(&x);
+    let y_sparse = CsrMatrix::from_dense(&y);
+
+    for metric in &["cosine", "dot", "euclidean"] {
+        let dense = ndarray_extension::pairwise_similarity(&x, &y, metric);
+        let sparse = ndarray_extension::sparse_pairwise_similarity(&x_sparse, &y_sparse, metric);
+        assert!(dense.iter().zip(sparse.iter()).all(|(a, b)| (a - b).abs() < 1e-12));
+
+        let dense_neighbors = ndarray_extension::nearest_neighbors(&x, &y, 2, metric, 2);
+        let sparse_neighbors = ndarray_extension::sparse_nearest_neighbors(&x_sparse, &y_sparse, 2, metric);
+        for (a, b) in dense_neighbors.iter().zip(sparse_neighbors.iter()) {
+            assert_eq!(a.iter().map(|e| e.0).collect::<Vec<usize>>(), b.iter().map(|e| e.0).collect::<Vec<usize>>());
+        }
+    }
+}
+
+#[test]
+fn test_bincount(){
+    let x = array![
+        [1.5f32, 0.0, 0.0, 2.0],
+        [0.0, 0.0, 0.0, 0.0],
+        [0.0, 3.0, 1.0, 7.0]];
+
+    let ans_columns = array![1u64, 1, 1, 2];
+    let ans_rows = array![2u64, 0, 3];
+
+    assert_eq!(ans_columns, ndarray_extension::bincount(&x, Axis(0)));
+    assert_eq!(ans_rows, ndarray_extension::bincount(&x, Axis(1)));
+
+    // Views, including transposed ones
+    assert_eq!(ans_rows, ndarray_extension::bincount(&x.t(), Axis(0)));
+    assert_eq!(array![1u64, 1], ndarray_extension::bincount(&x.slice(s![1.., 2..]), Axis(0)));
+
+    // Sparse version gives the same counts
+    let x_sparse = CsrMatrix::from_dense(&x);
+    assert_eq!(ans_columns, ndarray_extension::sparse_bincount(&x_sparse, Axis(0)));
+    assert_eq!(ans_rows, ndarray_extension::sparse_bincount(&x_sparse, Axis(1)));
+}
+
+#[test]
+#[should_panic(expected = "axis must be Axis(0) or Axis(1)")]
+fn test_bincount_invalid_axis(){
+    let x = array![[1.0f64, 0.0], [0.0, 2.0]];
+    ndarray_extension::bincount(&x, Axis(2));
+}
+
+#[test]
+fn test_normalize_inplace(){
+    let x = array![
+        [3.0, 0.0, -4.0],
+        [0.0, 0.0, 0.0],
+        [1.0, 1.0, 2.0]];
+
+    // Zero rows stay zero instead of becoming NaN
+    let l2 = ndarray_extension::l2_normalize(x.clone());
+    assert_eq!(array![0.6, 0.0, -0.8], l2.row(0));
+    assert_eq!(array![0.0, 0.0, 0.0], l2.row(1));
+    let l1 = ndarray_extension::l1_normalize(x.clone());
+    assert!(l1.iter().all(|e| e.is_finite()));
+
+    // Columns, with max norm
+    let mut columns = x.clone();
+    ndarray_extension::normalize_inplace(&mut columns, "max", Axis(0), 0.);
+    assert_eq!(array![
+        [1.0, 0.0, -1.0],
+        [0.0, 0.0, 0.0],
+        [1.0/3., 1.0, 0.5]], columns);
+
+    // Lanes with norms up to epsilon are left unchanged
+    let mut rows = x.mapv(|e| e as f32);
+    ndarray_extension::normalize_inplace(&mut rows.view_mut(), "l1", Axis(1), 4.0f32);
+    assert_eq!(array![3.0/7., 0.0, -4.0/7.], rows.row(0));
+    assert_eq!(array![1.0, 1.0, 2.0], rows.row(2));
+
+    assert_eq!(array![5.0, 0.0, 6.0f64.sqrt()], ndarray_extension::norms(&x, "l2", Axis(1)));
+}
+
+#[test]
+fn test_top_k_nan(){
+    let candidates = vec![(0, 0.5), (1, f64::NAN), (2, 0.9), (3, f64::NEG_INFINITY), (4, f64::NAN)];
+
+    // NaN ranks below all other values
+    let top = ndarray_extension::top_k(candidates.clone(), 3);
+    assert_eq!(vec![(2, 0.9), (0, 0.5), (3, f64::NEG_INFINITY)], top);
+
+    let top = ndarray_extension::top_k(candidates, 5);
+    assert_eq!(vec![2, 0, 3, 1, 4], top.iter().map(|&(index, _)| index).collect::<Vec<usize>>());
+    assert!(top[3].1.is_nan() && top[4].1.is_nan());
+}
@@ -0,0 +1,82 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use ndarray::Array2;
+use vectorizer::nmf::Nmf;
+use vectorizer::tfidfvectorizer::TfidfVectorizer;
+
+fn frobenius(x: &Array2<f64>) -> f64 {
+    x.iter().map(|e| e * e).sum::<f64>().sqrt()
+}
+
+fn low_rank_matrix() -> Array2<f64> {
+    let w = array![[1.0, 0.0], [2.0, 0.1], [0.0, 3.0], [0.5, 1.0], [0.0, 0.5]];
+    let h = array![[1.0, 2.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 2.0, 0.5]];
+    w.dot(&h)
+}
+
+#[test]
+fn test_solvers_reconstruct_low_rank(){
+    let x = low_rank_matrix();
+    for &(solver, beta_loss) in &[("cd", "frobenius"), ("mu", "frobenius"), ("mu", "kullback-leibler")] {
+        let mut nmf = Nmf::new(2);
+        nmf.solver = solver.to_string();
+        nmf.beta_loss = beta_loss.to_string();
+        nmf.max_iter = 1000;
+        nmf.tol = 1e-8;
+        let w = nmf.fit_transform(&x);
+
+        assert!(w.iter().chain(nmf.components_.iter()).all(|&e| e >= 0.));
+        let error = frobenius(&(&x - &w.dot(&nmf.components_)));
+        assert!(error < 0.05 * frobenius(&x), "{} {}: {}", solver, beta_loss, error);
+        assert!(nmf.n_iter_ > 0 && nmf.n_iter_ <= 1000);
+    }
+}
+
+#[test]
+fn test_transform_with_fixed_components(){
+    let x = low_rank_matrix();
+    let mut nmf = Nmf::new(2);
+    nmf.max_iter = 1000;
+    nmf.tol = 1e-8;
+    nmf.fit(&x);
+
+    let w = nmf.transform(&x.slice(s![..2, ..]).to_owned());
+    let error = frobenius(&(&x.slice(s![..2, ..]) - &w.dot(&nmf.components_)));
+    assert_eq!((2, 2), w.dim());
+    assert!(error < 0.05 * frobenius(&x));
+}
+
+#[test]
+fn test_top_terms(){
+    let docs = vec![
+        "cat dog cat",
+        "dog cat pet",
+        "stocks markets",
+        "markets stocks trade",
+    ];
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(docs);
+    let mut nmf = Nmf::new(2);
+    nmf.fit(&x);
+
+    let top_terms = nmf.top_terms(&vectorizer.get_feature_names(), 2);
+    let mut topics: Vec<Vec<String>> = top_terms.into_iter()
+        .map(|terms| {
+            let mut terms: Vec<String> = terms.into_iter().map(|(term, _)| term).collect();
+            terms.sort();
+            terms
+        })
+        .collect();
+    topics.sort();
+    assert_eq!(vec![vec!["cat", "dog"], vec!["markets", "stocks"]], topics);
+}
+
+#[test]
+#[should_panic]
+fn test_cd_rejects_kullback_leibler(){
+    let mut nmf = Nmf::new(2);
+    nmf.beta_loss = "kullback-leibler".to_string();
+    nmf.fit(&low_rank_matrix());
+}
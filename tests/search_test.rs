@@ -0,0 +1,105 @@
+extern crate vectorizer;
+
+use vectorizer::search::{InvertedIndex, Posting};
+use vectorizer::tfidfvectorizer::TfidfVectorizer;
+
+fn corpus() -> Vec<&'static str> {
+    vec![
+        "the quick brown fox",
+        "the lazy dog sleeps",
+        "the quick dog jumps over the lazy fox",
+        "a brown dog",
+    ]
+}
+
+#[test]
+fn test_postings(){
+    let mut index = InvertedIndex::new((1, 1), "lower");
+    index.fit(corpus());
+
+    assert_eq!(4, index.num_docs());
+    // Single character tokens such as "a" are dropped by the Tokenizer
+    assert_eq!(vec![4, 4, 8, 2], index.doc_lengths_);
+    assert_eq!(4.5, index.avg_doc_length_);
+
+    let doc_ids: Vec<(usize, u64)> = index.postings_["the"].iter()
+        .map(|p| (p.doc_id, p.tf))
+        .collect();
+    assert_eq!(vec![(0, 1), (1, 1), (2, 2)], doc_ids);
+}
+
+#[test]
+fn test_bm25_scores(){
+    let mut index = InvertedIndex::new((1, 1), "lower");
+    index.fit(corpus());
+
+    // "brown": df = 2 in 4 documents
+    let idf = (1f64 + 2.5 / 2.5).ln();
+    let expected = |tf: f64, dl: f64| idf * tf * 2.2 / (tf + 1.2 * (0.25 + 0.75 * dl / 4.5));
+    let results = index.search("Brown", 10);
+    assert_eq!(2, results.len());
+    assert_eq!(3, results[0].0);
+    assert!((results[0].1 - expected(1., 2.)).abs() < 1e-12);
+    assert_eq!(0, results[1].0);
+    assert!((results[1].1 - expected(1., 4.)).abs() < 1e-12);
+}
+
+#[test]
+fn test_search(){
+    let mut index = InvertedIndex::new((1, 1), "lower");
+    index.fit(corpus());
+
+    let results = index.search("lazy dog", 10);
+    let doc_ids: Vec<usize> = results.iter().map(|&(doc_id, _)| doc_id).collect();
+    assert_eq!(vec![1, 2, 3], doc_ids);
+
+    assert_eq!(1, index.search("lazy dog", 1).len());
+    assert!(index.search("cat", 10).is_empty());
+    assert!(index.search("", 10).is_empty());
+}
+
+#[test]
+fn test_bigrams(){
+    let mut index = InvertedIndex::new((1, 2), "lower");
+    index.fit(corpus());
+
+    // Documents 0 and 2 contain "fox", and only document 2 "lazy fox"
+    let results = index.search("lazy fox", 10);
+    assert_eq!(2, results[0].0);
+    assert!(index.postings_.contains_key("lazy fox"));
+}
+
+#[test]
+fn test_tfidf(){
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    vectorizer.fit(corpus());
+    let tfidf = vectorizer.transform(corpus());
+    let query = vectorizer.transform(vec!["quick fox"]);
+
+    let mut index = InvertedIndex::from_tfidf(vectorizer);
+    index.fit(corpus());
+
+    // Scores are cosine similarities of the Tf-Idf vectors
+    let results = index.search("quick fox", 10);
+    assert_eq!(2, results.len());
+    for &(doc_id, score) in results.iter() {
+        assert!((score - tfidf.row(doc_id).dot(&query.row(0))).abs() < 1e-12);
+    }
+    assert_eq!(0, results[0].0);
+
+    let posting = &index.postings_["fox"][0];
+    assert_eq!(Posting { doc_id: 0, tf: 1, weight: posting.weight }, *posting);
+}
+
+#[test]
+fn test_tfidf_unknown_tokens(){
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    vectorizer.fit(vec!["the quick brown fox"]);
+
+    // Tokens outside of the vocabulary are neither indexed nor searchable
+    let mut index = InvertedIndex::from_tfidf(vectorizer);
+    index.fit(corpus());
+    assert!(!index.postings_.contains_key("dog"));
+    assert!(index.search("dog", 10).is_empty());
+    assert_eq!(2, index.search("fox", 10).len());
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "serde")]
+extern crate vectorizer;
+
+use vectorizer::countvectorizer::CountVectorizer;
+use vectorizer::serialization::{Format, SerializationError, FORMAT_VERSION};
+use vectorizer::tfidfvectorizer::TfidfVectorizer;
+
+
+fn train_docs() -> Vec<&'static str> {
+    vec![
+        "apple banana apple",
+        "banana orange orange",
+        "grape apple melon",
+        "melon banana grape kiwi"]
+}
+
+fn test_docs() -> Vec<&'static str> {
+    vec![
+        "apple kiwi unknown",
+        "banana banana orange grape",
+        ""]
+}
+
+#[test]
+fn test_countvectorizer_round_trip(){
+    let mut vectorizer = CountVectorizer::new((1, 2), "lower");
+    vectorizer.fit(train_docs());
+
+    for &format in &[Format::Json, Format::Binary] {
+        let mut saved: Vec<u8> = Vec::new();
+        vectorizer.save(&mut saved, format).unwrap();
+        let loaded = CountVectorizer::load(saved.as_slice(), format).unwrap();
+
+        assert_eq!(vectorizer.ngram_range, loaded.ngram_range);
+        assert_eq!(vectorizer.case, loaded.case);
+        assert_eq!(vectorizer.vocabulary_, loaded.vocabulary_);
+        assert_eq!(vectorizer.transform(test_docs()), loaded.transform(test_docs()));
+    }
+}
+
+#[test]
+fn test_tfidfvectorizer_round_trip(){
+    let mut vectorizer = TfidfVectorizer::new((1, 2), "lower");
+    vectorizer.sublinear_tf = true;
+    vectorizer.norm = "pivoted".to_string();
+    vectorizer.slope = 0.3;
+    vectorizer.fit(train_docs());
+
+    for &format in &[Format::Json, Format::Binary] {
+        let mut saved: Vec<u8> = Vec::new();
+        vectorizer.save(&mut saved, format).unwrap();
+        let mut loaded = TfidfVectorizer::load(saved.as_slice(), format).unwrap();
+
+        assert_eq!(vectorizer.vocabulary_, loaded.vocabulary_);
+        assert_eq!(vectorizer.idf_, loaded.idf_);
+        assert_eq!(vectorizer.pivot_, loaded.pivot_);
+        assert_eq!(vectorizer.transform(test_docs()), loaded.transform(test_docs()));
+
+        // partial_fit continues from the saved document frequencies
+        let mut continued = TfidfVectorizer::load(saved.as_slice(), format).unwrap();
+        continued.partial_fit(test_docs());
+        loaded.fit(train_docs().into_iter().chain(test_docs()).collect());
+        assert_eq!(loaded.idf_, continued.idf_);
+    }
+}
+
+#[test]
+fn test_binary_is_smaller_than_json(){
+    let mut vectorizer = TfidfVectorizer::new((1, 2), "lower");
+    vectorizer.fit(train_docs());
+
+    let mut json: Vec<u8> = Vec::new();
+    let mut binary: Vec<u8> = Vec::new();
+    vectorizer.save(&mut json, Format::Json).unwrap();
+    vectorizer.save(&mut binary, Format::Binary).unwrap();
+    assert!(binary.len() < json.len());
+}
+
+#[test]
+fn test_unsupported_version(){
+    let json = format!(
+        "{{\"format_version\": {}, \"ngram_range\": [1, 1], \"case\": \"lower\", \"vocabulary\": []}}",
+        FORMAT_VERSION + 1);
+    match CountVectorizer::load(json.as_bytes(), Format::Json) {
+        Err(SerializationError::UnsupportedVersion(version)) => assert_eq!(FORMAT_VERSION + 1, version),
+        _ => panic!("expected UnsupportedVersion"),
+    }
+
+    let mut saved: Vec<u8> = Vec::new();
+    CountVectorizer::new((1, 1), "lower").save(&mut saved, Format::Binary).unwrap();
+    saved[0] = 0xff;
+    match CountVectorizer::load(saved.as_slice(), Format::Binary) {
+        Err(SerializationError::UnsupportedVersion(_)) => {},
+        _ => panic!("expected UnsupportedVersion"),
+    }
+}
+
+#[test]
+fn test_invalid_model(){
+    let json = "{\"format_version\": 1, \"ngram_range\": [1, 1], \"case\": \"lower\", \"stop_words\": [], \"vocabulary\": [\"apple\", \"apple\"]}";
+    match CountVectorizer::load(json.as_bytes(), Format::Json) {
+        Err(SerializationError::InvalidModel(_)) => {},
+        _ => panic!("expected InvalidModel"),
+    }
+
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    vectorizer.norm = "pivoted".to_string();
+    vectorizer.fit(train_docs());
+    let mut saved: Vec<u8> = Vec::new();
+    vectorizer.save(&mut saved, Format::Json).unwrap();
+    let saved = String::from_utf8(saved).unwrap();
+    assert!(TfidfVectorizer::load(saved.as_bytes(), Format::Json).is_ok());
+
+    // Corrupt one field at a time
+    let corrupted = vec![
+        saved.replace("\"banana\"", "\"apple\""),
+        saved.replace("\"idf\":[", "\"idf\":[1.0,"),
+        saved.replace("\"df\":[", "\"df\":[1,"),
+        saved.replace("\"n_samples\":4", "\"n_samples\":0"),
+        saved.replace("\"pivot\":null", "\"pivot\":10.0"),
+        saved.replace("\"stop_words\":[]", "\"stop_words\":[\"the\"]"),
+    ];
+    for json in corrupted {
+        assert_ne!(saved, json);
+        match TfidfVectorizer::load(json.as_bytes(), Format::Json) {
+            Err(SerializationError::InvalidModel(_)) => {},
+            _ => panic!("expected InvalidModel for {}", json),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+extern crate vectorizer;
+
+use vectorizer::simhash::{self, SimHash, SimHashIndex};
+use vectorizer::tfidfvectorizer::TfidfVectorizer;
+
+#[test]
+fn test_fingerprint(){
+    assert_eq!(0, simhash::fingerprint(&[]));
+    assert_eq!(3, simhash::hamming_distance(0b1011, 0b0110));
+
+    // Opposite weights of the same token cancel out
+    assert_eq!(0, simhash::fingerprint(&[("token", 1.0), ("token", -1.0)]));
+
+    // The heaviest token wins on bits where the hashes disagree
+    let heavy = simhash::fingerprint(&[("alpha", 3.0), ("beta", 1.0)]);
+    assert_eq!(simhash::hash64("alpha"), heavy);
+}
+
+#[test]
+fn test_simhash(){
+    let docs = vec![
+        "shares of the carmaker rose sharply after strong quarterly sales figures",
+        "shares of the carmaker rose sharply after strong quarterly sales figures were published",
+        "the recipe needs flour sugar butter and two eggs",
+        "",
+    ];
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    vectorizer.fit(docs.clone());
+    let simhash = SimHash::new(vectorizer);
+    let fingerprints = simhash.transform(docs.clone());
+
+    assert_eq!(4, fingerprints.len());
+    assert_eq!(0, fingerprints[3]);
+    assert!(simhash::hamming_distance(fingerprints[0], fingerprints[1]) <= 10);
+    assert!(simhash::hamming_distance(fingerprints[0], fingerprints[2]) > 10);
+
+    // Fingerprints only depend on the Tf-Idf weights
+    let weights = simhash.vectorizer().transform(vec![docs[2]]);
+    let feature_names = simhash.vectorizer().get_feature_names();
+    let weighted_tokens: Vec<(&str, f64)> = feature_names.iter()
+        .zip(weights.row(0).iter())
+        .filter(|&(_, &weight)| weight != 0.)
+        .map(|(token, &weight)| (token.as_str(), weight))
+        .collect();
+    assert_eq!(simhash::fingerprint(&weighted_tokens), fingerprints[2]);
+}
+
+#[test]
+fn test_index(){
+    let base: u64 = 0x0123_4567_89ab_cdef;
+    let fingerprints = vec![
+        base,
+        base ^ 1 << 63 ^ 1,         // 2 bits away
+        base ^ 0xff,                // 8 bits away
+        !base,
+        base ^ 1 << 40 ^ 1 << 41 ^ 1 << 42,
+    ];
+
+    let mut index = SimHashIndex::new(3);
+    index.fit(&fingerprints);
+    assert_eq!(vec![(0, 0), (1, 2), (4, 3)], index.query(base));
+    assert_eq!(vec![(3, 0)], index.query(!base));
+    assert!(index.query(base ^ 0xffff_0000).is_empty());
+    assert_eq!(vec![(0, 1, 2), (0, 4, 3)], index.near_duplicate_pairs());
+
+    // The index finds the same fingerprints as a linear scan
+    for &k in [0, 1, 5, 12, 63].iter() {
+        let mut index = SimHashIndex::new(k);
+        index.fit(&fingerprints);
+        for &query in fingerprints.iter() {
+            let expected: Vec<(usize, u32)> = fingerprints.iter()
+                .map(|&f| simhash::hamming_distance(query, f))
+                .enumerate()
+                .filter(|&(_, distance)| distance <= k)
+                .collect();
+            assert_eq!(expected, index.query(query));
+        }
+    }
+}
+
+#[test]
+fn test_partial_fit(){
+    let mut index = SimHashIndex::new(1);
+    index.fit(&[0b0001, 0b1000]);
+    index.partial_fit(&[0b0011, u64::MAX]);
+
+    assert_eq!(vec![0b0001, 0b1000, 0b0011, u64::MAX], index.fingerprints_);
+    assert_eq!(vec![(0, 1), (2, 0)], index.query(0b0011));
+
+    index.fit(&[0b0011]);
+    assert_eq!(vec![(0, 0)], index.query(0b0011));
+}
+
+#[test]
+#[should_panic]
+fn test_k_too_large(){
+    SimHashIndex::new(64);
+}
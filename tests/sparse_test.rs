@@ -0,0 +1,34 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::sparse::CsrMatrix;
+
+
+#[test]
+fn test_from_dense_and_back(){
+    let x = array![
+        [0, 2, 0],
+        [0, 0, 0],
+        [1, 0, 3]];
+
+    let x_sparse = CsrMatrix::from_dense(&x);
+    assert_eq!((3, 3), x_sparse.dim());
+    assert_eq!(vec![0, 1, 1, 3], x_sparse.indptr);
+    assert_eq!(vec![1, 0, 2], x_sparse.indices);
+    assert_eq!(vec![2, 1, 3], x_sparse.data);
+    assert_eq!(x, x_sparse.to_dense());
+}
+
+#[test]
+fn test_transpose(){
+    let x = array![
+        [0., 2., 0., 0.],
+        [4., 0., 0., 5.],
+        [1., 0., 3., 0.]];
+
+    let x_t = CsrMatrix::from_dense(&x).transpose();
+    assert_eq!((4, 3), x_t.dim());
+    assert_eq!(x.t().to_owned(), x_t.to_dense());
+    assert_eq!((&[1usize, 2][..], &[4., 1.][..]), x_t.row(0));
+}
@@ -0,0 +1,100 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::supervisedtransformer::SupervisedTransformer;
+
+fn data() -> (ndarray::Array2<u64>, Vec<&'static str>) {
+    // 2 negative and 3 positive documents
+    let counts = array![
+        [1, 0, 2],
+        [1, 1, 0],
+        [0, 1, 1],
+        [0, 1, 0],
+        [1, 2, 0]];
+    (counts, vec!["neg", "neg", "pos", "pos", "pos"])
+}
+
+#[test]
+fn test_delta_idf(){
+    let (counts, labels) = data();
+    let mut transformer = SupervisedTransformer::new("delta_idf");
+    transformer.norm = "none".to_string();
+    let x = transformer.fit_transform(&counts, &labels);
+
+    assert_eq!(vec!["neg", "pos"], transformer.classes_);
+    assert_eq!(array![2, 3], transformer.class_count_);
+    assert_eq!(array![[2, 1, 1], [1, 3, 1]], transformer.df_);
+
+    // log2((P * N_t + 0.5) / (N * P_t + 0.5)) with P = 3 and N = 2
+    let weights = array![
+        (6.5f64 / 2.5).log2(),
+        (3.5f64 / 6.5).log2(),
+        (3.5f64 / 2.5).log2()];
+    assert!((&transformer.weights_ - &weights).mapv(f64::abs).sum() < 1e-12);
+    assert!(transformer.weights_[0] > 0.);
+    assert!(transformer.weights_[1] < 0.);
+
+    let expected = counts.mapv(|e| e as f64) * &weights;
+    assert!((&x - &expected).mapv(f64::abs).sum() < 1e-12);
+}
+
+#[test]
+fn test_tf_rf(){
+    let (counts, labels) = data();
+    let mut transformer = SupervisedTransformer::new("tf_rf");
+    transformer.fit(&counts, &labels);
+
+    // log2(2 + P_t / max(1, N_t))
+    let weights = array![(2.5f64).log2(), (5f64).log2(), (3f64).log2()];
+    assert!((&transformer.weights_ - &weights).mapv(f64::abs).sum() < 1e-12);
+
+    // Rows are L2-normalized by default, and empty rows stay at zero
+    let x = transformer.transform(&array![[0, 1, 1], [0, 0, 0]]);
+    let norm = (weights[1].powi(2) + weights[2].powi(2)).sqrt();
+    assert!((x[[0, 1]] - weights[1] / norm).abs() < 1e-12);
+    assert!((x[[0, 2]] - weights[2] / norm).abs() < 1e-12);
+    assert_eq!(array![0., 0., 0.], x.row(1));
+}
+
+#[test]
+fn test_sublinear_tf(){
+    let (counts, labels) = data();
+    let mut transformer = SupervisedTransformer::new("tf_rf");
+    transformer.sublinear_tf = true;
+    transformer.norm = "none".to_string();
+    let x = transformer.fit_transform(&counts, &labels);
+
+    assert!((x[[0, 2]] - (1. + 2f64.ln()) * transformer.weights_[2]).abs() < 1e-12);
+    assert_eq!(0., x[[0, 1]]);
+}
+
+#[test]
+fn test_pivoted_norm(){
+    let (counts, labels) = data();
+    let mut transformer = SupervisedTransformer::new("tf_rf");
+    transformer.norm = "pivoted".to_string();
+    let x = transformer.fit_transform(&counts, &labels);
+
+    // 9 unique terms over 5 documents
+    assert_eq!(1.8, transformer.pivot_);
+    let row_norm = 0.8 * 1.8 + 0.2 * 2.;
+    assert!((x[[0, 0]] - transformer.weights_[0] / row_norm).abs() < 1e-12);
+    assert!((x[[0, 2]] - 2. * transformer.weights_[2] / row_norm).abs() < 1e-12);
+}
+
+#[test]
+#[should_panic]
+fn test_more_than_two_classes(){
+    let (counts, _) = data();
+    let mut transformer = SupervisedTransformer::new("tf_rf");
+    transformer.fit(&counts, &["a", "b", "c", "a", "b"]);
+}
+
+#[test]
+#[should_panic]
+fn test_unknown_scheme(){
+    let (counts, labels) = data();
+    let mut transformer = SupervisedTransformer::new("idf");
+    transformer.fit(&counts, &labels);
+}
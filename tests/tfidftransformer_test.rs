@@ -0,0 +1,154 @@
+extern crate vectorizer;
+#[macro_use]
+extern crate ndarray;
+
+use vectorizer::tfidftransformer::TfidfTransformer;
+use vectorizer::tfidfvectorizer::TfidfVectorizer;
+
+
+#[test]
+fn test_pivot_learned_at_fit(){
+    let counts = array![
+        [1, 1, 0, 0],
+        [1, 1, 1, 1],
+        [0, 0, 1, 0]];
+
+    let mut transformer = TfidfTransformer::new();
+    transformer.norm = "pivoted".to_string();
+    transformer.fit(&counts);
+
+    // (2 + 4 + 1) unique terms over 3 documents
+    assert_eq!(7. / 3., transformer.pivot_);
+    assert_eq!(4, transformer.idf_.len());
+}
+
+#[test]
+fn test_pivoted_normalization(){
+    let counts = array![
+        [1, 0, 0, 0],
+        [1, 1, 1, 1]];
+
+    let mut transformer = TfidfTransformer::new();
+    transformer.smooth_idf = false;
+    transformer.norm = "pivoted".to_string();
+    transformer.pivot = Some(2.);
+    transformer.slope = 0.5;
+    let tfidf = transformer.fit_transform(&counts);
+    assert_eq!(2., transformer.pivot_);
+
+    // idf of column 0 is ln(2/2) + 1 = 1, others ln(2/1) + 1
+    let idf = 2f64.ln() + 1.;
+    let ans = array![
+        [1. / 1.5, 0., 0., 0.],
+        [1. / 3., idf / 3., idf / 3., idf / 3.]];
+    assert_eq!(ans, tfidf);
+}
+
+#[test]
+fn test_pivoted_empty_rows_stay_zero(){
+    let counts = array![
+        [1, 2, 0],
+        [0, 0, 0]];
+
+    // A slope of 1.0 gives a divisor of 0 for the empty row
+    let mut transformer = TfidfTransformer::new();
+    transformer.norm = "pivoted".to_string();
+    transformer.slope = 1.;
+    let tfidf = transformer.fit_transform(&counts);
+    assert!(tfidf.iter().all(|e| e.is_finite()));
+    assert_eq!(array![0., 0., 0.], tfidf.row(1));
+
+    // Same with a supplied pivot of 0
+    transformer.slope = 0.2;
+    transformer.pivot = Some(0.);
+    let tfidf = transformer.fit_transform(&counts);
+    assert_eq!(array![0., 0., 0.], tfidf.row(1));
+
+    // Fitting on an empty corpus learns a pivot of 0
+    let mut empty = TfidfTransformer::new();
+    empty.norm = "pivoted".to_string();
+    empty.fit(&array![[0u64, 0, 0]].slice(s![..0, ..]).to_owned());
+    assert_eq!(0., empty.pivot_);
+    assert_eq!(array![[0., 0., 0.]], empty.transform(&array![[0, 0, 0]]));
+}
+
+#[test]
+fn test_pivoted_favors_long_documents_over_l2(){
+    let counts = array![
+        [1, 1, 0, 0, 0, 0, 0, 0],
+        [1, 1, 1, 1, 1, 1, 1, 1]];
+
+    let mut l2 = TfidfTransformer::new();
+    let mut pivoted = TfidfTransformer::new();
+    pivoted.norm = "pivoted".to_string();
+    let tfidf_l2 = l2.fit_transform(&counts);
+    let tfidf_pivoted = pivoted.fit_transform(&counts);
+
+    // Ratio of the weight of a shared term between long and short documents
+    let ratio_l2 = tfidf_l2[[1, 0]] / tfidf_l2[[0, 0]];
+    let ratio_pivoted = tfidf_pivoted[[1, 0]] / tfidf_pivoted[[0, 0]];
+    assert!(ratio_pivoted > ratio_l2);
+}
+
+#[test]
+fn test_transform_with_fitted_idf(){
+    let train = array![
+        [2, 0, 1],
+        [1, 1, 0]];
+    let test = array![
+        [0, 3, 1]];
+
+    let mut transformer = TfidfTransformer::new();
+    transformer.norm = "none".to_string();
+    transformer.fit(&train);
+    let tfidf = transformer.transform(&test);
+
+    assert_eq!(0., tfidf[[0, 0]]);
+    assert_eq!(3. * transformer.idf_[1], tfidf[[0, 1]]);
+    assert_eq!(transformer.idf_[2], tfidf[[0, 2]]);
+}
+
+#[test]
+fn test_vectorizer_pivoted_norm(){
+    let docs = vec![
+        "apple banana",
+        "apple banana orange grape melon",
+        "orange"];
+
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    vectorizer.norm = "pivoted".to_string();
+    vectorizer.slope = 0.25;
+    let tfidf = vectorizer.fit_transform(docs);
+
+    assert_eq!((3, 5), tfidf.dim());
+    assert_eq!(8. / 3., vectorizer.pivot_);
+    assert_eq!(5, vectorizer.idf_.len());
+}
+
+#[test]
+fn test_partial_fit_with_growing_columns(){
+    let batch1 = array![
+        [1, 2],
+        [0, 1]];
+    let batch2 = array![
+        [1, 0, 3]];
+    let stacked = array![
+        [1, 2, 0],
+        [0, 1, 0],
+        [1, 0, 3]];
+
+    let mut transformer = TfidfTransformer::new();
+    transformer.norm = "pivoted".to_string();
+    transformer.partial_fit(&batch1);
+    transformer.partial_fit(&batch2);
+
+    let mut batch_transformer = TfidfTransformer::new();
+    batch_transformer.norm = "pivoted".to_string();
+    batch_transformer.fit(&stacked);
+
+    assert_eq!(array![2, 2, 1], transformer.df_);
+    assert_eq!(3, transformer.n_samples_);
+    assert_eq!(batch_transformer.idf_, transformer.idf_);
+    assert_eq!(batch_transformer.pivot_, transformer.pivot_);
+    assert_eq!(batch_transformer.transform(&stacked), transformer.transform(&stacked));
+}
@@ -1,3 +1,6 @@
+// The original tests are kept as written
+#![allow(clippy::vec_init_then_push, clippy::unnecessary_cast)]
+
 extern crate vectorizer;
 
 use vectorizer::tfidfvectorizer::TfidfVectorizer;
@@ -82,4 +85,133 @@ fn tfidf_example() {
 
     println!("CountVector :\n{:?}", x);
     println!("\n");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_partial_fit_matches_fit(){
+    let batches = vec![
+        vec!["apple banana apple", "banana orange"],
+        vec!["grape apple"],
+        vec!["melon banana grape", "orange orange kiwi"]];
+    let docs: Vec<&str> = batches.concat();
+
+    let mut vectorizer = TfidfVectorizer::new((1, 2), "lower");
+    let x = vectorizer.fit_transform(docs.clone());
+
+    let mut incremental = TfidfVectorizer::new((1, 2), "lower");
+    for batch in batches {
+        incremental.partial_fit(batch);
+    }
+
+    assert_eq!(5, incremental.n_samples_);
+    assert_eq!(vectorizer.vocabulary_, incremental.vocabulary_);
+    assert_eq!(vectorizer.df_, incremental.df_);
+    assert_eq!(vectorizer.idf_, incremental.idf_);
+    assert_eq!(x, incremental.transform(docs));
+}
+
+#[test]
+fn test_fit_then_transform(){
+    let docs = vec!["apple banana apple", "banana orange", "grape apple"];
+
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(docs.clone());
+    assert_eq!(x, vectorizer.transform(docs.clone()));
+
+    // fit() discards the previous state
+    vectorizer.fit(vec!["kiwi"]);
+    assert_eq!(1, vectorizer.vocabulary_.len());
+    assert_eq!(1, vectorizer.n_samples_);
+}
+
+#[test]
+fn test_f32_output(){
+    let docs = vec!["apple banana apple", "banana orange", "grape apple"];
+
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x_f32 = vectorizer.fit_transform_as::<f32>(docs.clone());
+    let x = vectorizer.transform(docs.clone());
+
+    // Weights are computed in f32, so they only match up to rounding
+    assert_eq!(x.dim(), x_f32.dim());
+    for (&e, &e_f32) in x.iter().zip(x_f32.iter()) {
+        assert!((e as f32 - e_f32).abs() < 1e-6);
+    }
+    assert_eq!(x_f32, vectorizer.transform_as::<f32>(docs));
+}
+
+#[test]
+fn test_inverse_transform(){
+    let docs = vec!["apple banana banana", "orange apple", "kiwi"];
+
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(docs);
+    let y = vectorizer.transform(vec!["banana unknown"]);
+
+    let tokens = vectorizer.inverse_transform(&x);
+    assert_eq!(vec!["apple", "banana"], tokens[0]);
+    assert_eq!(vec!["apple", "orange"], tokens[1]);
+    assert_eq!(vec![vec!["banana".to_string()]], vectorizer.inverse_transform(&y));
+
+    let weights = vectorizer.inverse_transform_with_weights(&x);
+    assert_eq!("orange", weights[1][0].0);
+    assert_eq!(x[[1, vectorizer.vocabulary_["orange"] as usize]], weights[1][0].1);
+    assert!(weights[1][0].1 > weights[1][1].1);
+}
+
+#[test]
+fn test_top_k_keywords(){
+    let docs = vec![
+        "apple apple apple banana orange",
+        "banana orange",
+        "kiwi"];
+
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let x = vectorizer.fit_transform(docs.clone());
+    let keywords = vectorizer.top_k_keywords(&x, 2);
+
+    assert_eq!(3, keywords.len());
+    assert_eq!(2, keywords[0].len());
+    assert_eq!("apple", keywords[0][0].0);
+    assert!(keywords[0][0].1 >= keywords[0][1].1);
+    assert_eq!(vec![("kiwi".to_string(), 1.)], keywords[2]);
+
+    // Same as sorting the whole row
+    let mut all = vectorizer.inverse_transform_with_weights(&x);
+    for row in all.iter_mut() {
+        row.truncate(2);
+    }
+    assert_eq!(keywords[0][0], all[0][0]);
+    assert_eq!(keywords[2], all[2]);
+
+    assert_eq!(keywords, vectorizer.extract_keywords(docs, 2));
+    assert!(vectorizer.top_k_keywords(&x, 0).iter().all(|row| row.is_empty()));
+}
+
+#[test]
+fn test_inverse_transform_nan_last(){
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    let mut x = vectorizer.fit_transform(vec!["apple banana orange kiwi"]);
+    x[[0, 1]] = f64::NAN;
+    x[[0, 2]] = 2.;
+
+    // The NaN weight ranks last, as with top_k_keywords()
+    let weights = vectorizer.inverse_transform_with_weights(&x);
+    let tokens: Vec<&str> = weights[0].iter().map(|(token, _)| token.as_str()).collect();
+    assert_eq!(vec!["orange", "apple", "kiwi", "banana"], tokens);
+    assert!(weights[0][3].1.is_nan());
+    assert_eq!(weights[0][..3], vectorizer.top_k_keywords(&x, 3)[0][..]);
+}
+
+#[test]
+fn test_unknown_document_is_zero_row(){
+    let mut vectorizer = TfidfVectorizer::new((1, 1), "lower");
+    vectorizer.fit(vec!["apple banana", "banana orange"]);
+
+    for &norm in &["l1", "l2", "max"] {
+        vectorizer.norm = norm.to_string();
+        let x = vectorizer.transform(vec!["kiwi", "apple"]);
+        assert!(x.row(0).iter().all(|&e| e == 0.0));
+        assert!(x.row(1).iter().any(|&e| e > 0.0));
+    }
+}